@@ -1,52 +1,229 @@
 ﻿using System;
+using System.Collections.Generic;
+using System.Linq;
+using System.Reflection;
+using System.Runtime.CompilerServices;
 using System.Runtime.InteropServices;
+using System.Text;
 
+/// <summary>
+/// Versioned table of bridge entry points handed to rust, mirrors the rust `BridgeImpl`.
+/// </summary>
+/// <remarks>
+/// Rust validates `Version`, `Size` and `Capabilities` before calling any entry point so
+/// a mismatched bridge assembly is rejected instead of being called with the wrong layout.
+/// </remarks>
 [StructLayout(LayoutKind.Sequential)]
 public unsafe struct Bridge {
+  /// <summary>
+  /// Bumped whenever the layout or semantics of the table change, must match the rust
+  /// `BRIDGE_VERSION`.
+  /// </summary>
+  public const uint CurrentVersion = 1;
 
-  [UnmanagedFunctionPointer(CallingConvention.StdCall)]
-  public unsafe delegate IntPtr ReleaseDelegate(IntPtr handle);
+  uint Version;
+  uint Size;
+  Capabilities Capabilities;
 
-  [UnmanagedFunctionPointer(CallingConvention.StdCall)]
-  public unsafe delegate int GetMethodDelegate(
-    [MarshalAs(UnmanagedType.LPUTF8Str)]
-  string path,
-    int* types,
+  delegate* unmanaged[Cdecl]<IntPtr, BridgeResult> Release;
+  delegate* unmanaged[Cdecl]<byte*, uint, byte*, ushort, BridgeResult> GetMethod;
+  delegate* unmanaged[Cdecl]<IntPtr, byte, BridgeResult> Alloc;
+  delegate* unmanaged[Cdecl]<
+    IntPtr, byte*, uint, MemberKind, Binding, byte*, ushort, BridgeResult
+  > GetMember;
+  delegate* unmanaged[Cdecl]<byte*, uint, BridgeResult> FindType;
+
+  // The table is handed out once and never freed, rust keeps its own copy
+  static readonly IntPtr Instance = Create();
+
+  // Resolved thunks keyed by path and signature
+  static readonly Dictionary<string, Thunk> Thunks = new Dictionary<string, Thunk>();
+
+  /// <summary>
+  /// Frees a handle previously handed to rust.
+  /// </summary>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  public static BridgeResult ReleaseImp(IntPtr handle) {
+    return Handles.Free(handle)
+      ? BridgeResult.Ok()
+      : BridgeResult.Err(BridgeError.InvalidHandle);
+  }
+
+  /// <summary>
+  /// Allocates a new handle of `kind` to the target of `handle`.
+  /// </summary>
+  /// <remarks>
+  /// Returns a zero handle when the target of a weak handle has been collected.
+  /// </remarks>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  public static BridgeResult AllocImp(IntPtr handle, byte kind) {
+    if (!Handles.Contains(handle)) {
+      return BridgeResult.Err(BridgeError.InvalidHandle);
+    }
+
+    try {
+      return BridgeResult.Ok(Handles.Alloc(Handles.Target(handle), (GCHandleType)kind));
+    } catch (ArgumentException) {
+      // Thrown by `GCHandle.Alloc` when pinning objects with non-blittable data
+      return BridgeResult.Err(BridgeError.NotPinnable);
+    }
+  }
+
+  /// <summary>
+  /// Resolves a static method from `Namespace.Type.Method`, optionally assembly qualified,
+  /// and returns a pointer to a <see cref="Thunk"/> calling it.
+  /// </summary>
+  /// <remarks>
+  /// The last entry of `types` is the return type, the rest are the argument types.
+  /// </remarks>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  public static BridgeResult GetMethodImp(
+    byte* path,
+    uint pathLength,
+    byte* types,
     ushort typesLength
-  );
+  ) {
+    if (typesLength == 0) {
+      return BridgeResult.Err(BridgeError.MethodNotFound);
+    }
+
+    var name = Encoding.UTF8.GetString(path, (int)pathLength);
+    var ids = TypeId.ReadMany(types, typesLength);
+    var args = ids.Take(ids.Length - 1).ToArray();
+    var ret = ids[ids.Length - 1];
+    var key = $"{name}({string.Join(", ", args.Select(arg => arg.ToString()))}) -> {ret}";
+
+    lock (Thunks) {
+      if (Thunks.TryGetValue(key, out var cached)) {
+        return BridgeResult.Ok(cached.Pointer);
+      }
 
-  IntPtr Release;
-  IntPtr GetMethod;
-  int Test;
+      if (!Resolver.TrySplitPath(name, out var typeName, out var methodName)) {
+        return BridgeResult.Err(BridgeError.InvalidPath);
+      }
 
-  public static IntPtr ReleaseImp(IntPtr handle) {
-    Console.WriteLine("ReleaseImp(IntPtr handle)");
+      var type = Resolver.FindType(typeName);
+      if (type == null) {
+        return BridgeResult.Err(BridgeError.TypeNotFound);
+      }
 
-    throw new NotImplementedException();
+      var method = Resolver.FindMethod(
+        type,
+        methodName,
+        BindingFlags.Public | BindingFlags.Static,
+        args,
+        ret,
+        out var error
+      );
+
+      if (method == null) {
+        return BridgeResult.Err(error);
+      }
+
+      var thunk = Thunk.ForMethod(method, args, ret);
+      Thunks.Add(key, thunk);
+
+      return BridgeResult.Ok(thunk.Pointer);
+    }
   }
 
-  public static int GetMethodImp(
-    [MarshalAs(UnmanagedType.LPUTF8Str)]
-    string path,
-    int* types,
+  /// <summary>
+  /// Resolves a member of the object `target` points to and returns a pointer to a
+  /// <see cref="Thunk"/> accessing it.
+  /// </summary>
+  /// <remarks>
+  /// Static members are resolved on the <see cref="Type"/> `target` points to.  Fields and
+  /// properties are read when `types` only holds a return type and written when it holds a
+  /// single argument followed by `Void`.  Instance methods are invoked virtually and
+  /// constructors are resolved with a static binding on the type being constructed.
+  /// </remarks>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  public static BridgeResult GetMemberImp(
+    IntPtr target,
+    byte* name,
+    uint nameLength,
+    MemberKind kind,
+    Binding binding,
+    byte* types,
     ushort typesLength
   ) {
-    Console.WriteLine("GetMethodImp(..)");
+    if (typesLength == 0) {
+      return BridgeResult.Err(BridgeError.TypeMismatch);
+    }
+
+    if (!Handles.Contains(target)) {
+      return BridgeResult.Err(BridgeError.InvalidHandle);
+    }
+
+    var self = Handles.Target(target);
+    var type = binding == Binding.Static ? self as Type : self?.GetType();
+    if (type == null) {
+      return BridgeResult.Err(BridgeError.InvalidHandle);
+    }
+
+    var memberName = Encoding.UTF8.GetString(name, (int)nameLength);
+    var ids = TypeId.ReadMany(types, typesLength);
+    var args = ids.Take(ids.Length - 1).ToArray();
+    var ret = ids[ids.Length - 1];
+    var key = $"{type.AssemblyQualifiedName}:{binding}:{kind}:{memberName}"
+      + $"({string.Join(", ", args.Select(arg => arg.ToString()))}) -> {ret}";
 
-    throw new NotImplementedException();
+    lock (Thunks) {
+      if (Thunks.TryGetValue(key, out var cached)) {
+        return BridgeResult.Ok(cached.Pointer);
+      }
+
+      var flags = BindingFlags.Public
+        | BindingFlags.FlattenHierarchy
+        | (binding == Binding.Static ? BindingFlags.Static : BindingFlags.Instance);
+
+      var thunk = Resolver.FindMember(type, memberName, kind, flags, args, ret, out var error);
+      if (thunk == null) {
+        return BridgeResult.Err(error);
+      }
+
+      Thunks.Add(key, thunk);
+
+      return BridgeResult.Ok(thunk.Pointer);
+    }
+  }
+
+  /// <summary>
+  /// Finds a type by its, optionally assembly qualified, name and returns a handle to its
+  /// <see cref="Type"/>.
+  /// </summary>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  public static BridgeResult FindTypeImp(byte* name, uint nameLength) {
+    var type = Resolver.FindType(Encoding.UTF8.GetString(name, (int)nameLength));
+    if (type == null) {
+      return BridgeResult.Err(BridgeError.TypeNotFound);
+    }
+
+    return BridgeResult.Ok(Handles.Alloc(type));
   }
 
   public static IntPtr GetBridge() {
-    Console.WriteLine("GetBridge()");
+    return Instance;
+  }
 
-    var bridge = new Bridge {
-      Release = Marshal.GetFunctionPointerForDelegate((ReleaseDelegate)ReleaseImp),
-      GetMethod = Marshal.GetFunctionPointerForDelegate((GetMethodDelegate)GetMethodImp),
-      Test = 69420
+  static IntPtr Create() {
+    var handle = Marshal.AllocHGlobal(sizeof(Bridge));
+
+    *(Bridge*)handle = new Bridge {
+      Version = CurrentVersion,
+      Size = (uint)sizeof(Bridge),
+      Capabilities = Capabilities.Members
+        | Capabilities.Constructors
+        | Capabilities.Exceptions
+        | Capabilities.Arrays
+        | Capabilities.Enumerables,
+      Release = &ReleaseImp,
+      GetMethod = &GetMethodImp,
+      Alloc = &AllocImp,
+      GetMember = &GetMemberImp,
+      FindType = &FindTypeImp,
     };
 
-    var handle = Marshal.AllocHGlobal(sizeof(Bridge));
-    Marshal.StructureToPtr(bridge, handle, false);
     return handle;
   }
 }
@@ -56,59 +233,85 @@ public enum BridgeStatus : byte {
   Err
 }
 
-public enum BridgeError : byte { }
+/// <summary>
+/// Mirrors the rust `BridgeError` enum, order matters.
+/// </summary>
+public enum BridgeError : byte {
+  MethodNotFound,
+  TypeNotFound,
+  AmbiguousMatch,
+  InvalidPath,
+  InvalidHandle,
+  NotPinnable,
+  MemberNotFound,
+  TypeMismatch,
+  VersionMismatch,
+  LayoutMismatch,
+  MissingCapability,
+  TooLong,
+}
 
-[StructLayout(LayoutKind.Explicit)]
-public struct BridgeResult<T> {
-  [FieldOffset(0)]
-  public BridgeStatus Status;
-  [FieldOffset(1)]
-  public T Value;
-  [FieldOffset(1)]
-  public BridgeError Error;
+/// <summary>
+/// Mirrors the rust `Capabilities` bits, values matter.
+/// </summary>
+[Flags]
+public enum Capabilities : ulong {
+  None = 0,
+  Members = 1 << 0,
+  Constructors = 1 << 1,
+  Exceptions = 1 << 2,
+  Arrays = 1 << 3,
+  Enumerables = 1 << 4,
+}
 
-  public static BridgeResult<T> Ok(T value) {
-    return new BridgeResult<T> {
-      Status = BridgeStatus.Ok,
-      Value = value,
-      Error = default!,
-    };
-  }
+/// <summary>
+/// Mirrors the rust `MemberKind` enum, order matters.
+/// </summary>
+public enum MemberKind : byte {
+  Field,
+  Property,
+  Method,
+  Constructor,
+}
 
-  public static BridgeResult<T> Err(BridgeError err) {
-    return new BridgeResult<T> {
-      Status = BridgeStatus.Err,
-      Value = default!,
-      Error = err,
-    };
-  }
+/// <summary>
+/// Mirrors the rust `Binding` enum, order matters.
+/// </summary>
+public enum Binding : byte {
+  Instance,
+  Static,
 }
 
-[StructLayout(LayoutKind.Explicit)]
+/// <summary>
+/// Mirrors the rust `#[repr(C, u8)]` `BridgeResult<T>` for pointer sized values.
+/// </summary>
+/// <remarks>
+/// The payload is aligned to pointer size, on error the low byte of `Value` holds the
+/// <see cref="BridgeError"/>.
+/// </remarks>
+[StructLayout(LayoutKind.Sequential)]
 public struct BridgeResult {
-  [FieldOffset(0)]
   public BridgeStatus Status;
-  [FieldOffset(1)]
   public IntPtr Value;
-  [FieldOffset(1)]
-  public BridgeError Error;
 
   public static BridgeResult Ok() {
+    return Ok(IntPtr.Zero);
+  }
+
+  public static BridgeResult Ok(IntPtr value) {
     return new BridgeResult {
       Status = BridgeStatus.Ok,
-      Value = IntPtr.Zero,
-      Error = default!,
+      Value = value,
     };
   }
 
   public static BridgeResult Err(BridgeError err) {
     return new BridgeResult {
-      Value = IntPtr.Zero,
       Status = BridgeStatus.Err,
-      Error = err,
+      Value = (IntPtr)(byte)err,
     };
   }
 }
 
-[UnmanagedFunctionPointer(CallingConvention.StdCall)]
-public delegate IntPtr GetBridgeDelegate();
\ No newline at end of file
+[UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+public delegate IntPtr GetBridgeDelegate();
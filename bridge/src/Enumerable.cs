@@ -0,0 +1,180 @@
+using System;
+using System.Collections;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+using System.Threading;
+
+/// <summary>
+/// Mirrors the rust `NextStatus` enum, order matters.
+/// </summary>
+public enum NextStatus : byte {
+  Done,
+  Item,
+  Failed,
+}
+
+/// <summary>
+/// Native representation of the rust `RawIterator` marshal type.
+/// </summary>
+/// <remarks>
+/// `Next` advances the iterator and points `item` to the native representation of the next
+/// element, which stays valid until the following call.  Taking ownership of the iterator
+/// clears `State` so rust does not release it once the call returns.
+/// </remarks>
+[StructLayout(LayoutKind.Sequential)]
+public unsafe struct RawIterator {
+  public IntPtr State;
+  public delegate* unmanaged[Cdecl]<IntPtr, void**, NextStatus> Next;
+  public delegate* unmanaged[Cdecl]<IntPtr, void> Release;
+
+  /// <summary>
+  /// Takes ownership of the iterator and wraps it in a lazy
+  /// <see cref="NativeEnumerable{T}"/> of `elementType`, returns null for a null reference.
+  /// </summary>
+  public object? ToEnumerable(TypeId element, Type elementType) {
+    if (State == IntPtr.Zero) {
+      return null;
+    }
+
+    var iterator = new NativeIterator(this);
+    State = IntPtr.Zero;
+
+    return Activator.CreateInstance(
+      typeof(NativeEnumerable<>).MakeGenericType(elementType),
+      iterator,
+      element
+    );
+  }
+}
+
+/// <summary>
+/// Owns a rust iterator, releasing it once disposed or collected.
+/// </summary>
+/// <remarks>
+/// Advancing and releasing are serialized, enumerators can be disposed on another thread
+/// while rust is still producing the next element.
+/// </remarks>
+public sealed unsafe class NativeIterator : IDisposable {
+  readonly object Gate = new object();
+  RawIterator Raw;
+
+  public NativeIterator(RawIterator raw) {
+    Raw = raw;
+  }
+
+  ~NativeIterator() {
+    Release();
+  }
+
+  /// <summary>
+  /// Advances the iterator, reading the next element as `type`.
+  /// </summary>
+  public bool Next(TypeId element, Type type, out object? value) {
+    try {
+      lock (Gate) {
+        var state = Raw.State;
+        if (state == IntPtr.Zero) {
+          throw new ObjectDisposedException(nameof(NativeIterator));
+        }
+
+        // `item` is only valid until the next call, read it before releasing the lock
+        void* item;
+        switch (Raw.Next(state, &item)) {
+          case NextStatus.Done:
+            value = null;
+            return false;
+          case NextStatus.Item:
+            value = element.Read(item, type);
+            return true;
+          default:
+            throw new InvalidOperationException("Failed to marshal element of rust iterator");
+        }
+      }
+    } finally {
+      // The finalizer must not release the state while rust is using it
+      GC.KeepAlive(this);
+    }
+  }
+
+  public void Dispose() {
+    Release();
+    GC.SuppressFinalize(this);
+  }
+
+  void Release() {
+    lock (Gate) {
+      var state = Raw.State;
+      if (state != IntPtr.Zero) {
+        Raw.State = IntPtr.Zero;
+        Raw.Release(state);
+      }
+    }
+  }
+}
+
+/// <summary>
+/// Rust iterator exposed as a lazy <see cref="IEnumerable{T}"/> that can be enumerated
+/// once.
+/// </summary>
+public sealed class NativeEnumerable<T> : IEnumerable<T> {
+  readonly NativeIterator Iterator;
+  readonly TypeId Element;
+  int Enumerated;
+
+  public NativeEnumerable(NativeIterator iterator, TypeId element) {
+    Iterator = iterator;
+    Element = element;
+  }
+
+  public IEnumerator<T> GetEnumerator() {
+    if (Interlocked.Exchange(ref Enumerated, 1) != 0) {
+      throw new InvalidOperationException("Rust iterators can only be enumerated once");
+    }
+
+    return new Enumerator(Iterator, Element);
+  }
+
+  IEnumerator IEnumerable.GetEnumerator() {
+    return GetEnumerator();
+  }
+
+  sealed class Enumerator : IEnumerator<T> {
+    readonly NativeIterator Iterator;
+    readonly TypeId Element;
+    T Value = default!;
+    bool Done;
+
+    public Enumerator(NativeIterator iterator, TypeId element) {
+      Iterator = iterator;
+      Element = element;
+    }
+
+    public T Current => Value;
+
+    object? IEnumerator.Current => Value;
+
+    public bool MoveNext() {
+      if (Done) {
+        return false;
+      }
+
+      if (!Iterator.Next(Element, typeof(T), out var value)) {
+        // Release the rust iterator as soon as it is exhausted
+        Done = true;
+        Iterator.Dispose();
+        return false;
+      }
+
+      Value = (T)value!;
+      return true;
+    }
+
+    public void Reset() {
+      throw new NotSupportedException("Rust iterators can not be reset");
+    }
+
+    public void Dispose() {
+      Iterator.Dispose();
+    }
+  }
+}
@@ -0,0 +1,65 @@
+using System;
+using System.Collections.Generic;
+using System.Runtime.InteropServices;
+
+/// <summary>
+/// Table of every <see cref="GCHandle"/> handed out to rust.
+/// </summary>
+/// <remarks>
+/// Handles are only ever freed once and only if they were allocated here, so a bogus or
+/// double released handle from rust results in an error instead of heap corruption.
+/// </remarks>
+public static class Handles {
+  static readonly HashSet<IntPtr> Live = new HashSet<IntPtr>();
+
+  /// <summary>
+  /// Allocates a handle to `target`, returns zero for null references.
+  /// </summary>
+  public static IntPtr Alloc(object? target, GCHandleType kind = GCHandleType.Normal) {
+    if (target == null) {
+      return IntPtr.Zero;
+    }
+
+    var handle = GCHandle.ToIntPtr(GCHandle.Alloc(target, kind));
+    lock (Live) {
+      Live.Add(handle);
+    }
+
+    return handle;
+  }
+
+  public static bool Contains(IntPtr handle) {
+    lock (Live) {
+      return Live.Contains(handle);
+    }
+  }
+
+  /// <summary>
+  /// Gets the target of `handle`, zero is treated as a null reference.
+  /// </summary>
+  public static object? Target(IntPtr handle) {
+    if (handle == IntPtr.Zero) {
+      return null;
+    }
+
+    // Read under the lock, a concurrent `Free` could release the handle in between
+    lock (Live) {
+      if (!Live.Contains(handle)) {
+        throw new ArgumentException($"Unknown handle `{handle}`", nameof(handle));
+      }
+
+      return GCHandle.FromIntPtr(handle).Target;
+    }
+  }
+
+  public static bool Free(IntPtr handle) {
+    lock (Live) {
+      if (!Live.Remove(handle)) {
+        return false;
+      }
+
+      GCHandle.FromIntPtr(handle).Free();
+      return true;
+    }
+  }
+}
@@ -0,0 +1,335 @@
+using System;
+using System.Collections.Generic;
+using System.Linq;
+using System.Reflection;
+
+/// <summary>
+/// Resolves managed types and members from the names and `TypeId`s sent by rust.
+/// </summary>
+public static class Resolver {
+  /// <summary>
+  /// Splits `Namespace.Type.Method` or `Namespace.Type.Method, Assembly` into its type
+  /// and member names.
+  /// </summary>
+  public static bool TrySplitPath(string path, out string typeName, out string memberName) {
+    var comma = path.IndexOf(',');
+    var name = comma < 0 ? path : path.Substring(0, comma);
+    var assembly = comma < 0 ? null : path.Substring(comma + 1).Trim();
+    var dot = name.LastIndexOf('.');
+
+    typeName = "";
+    memberName = "";
+
+    if (dot <= 0 || dot == name.Length - 1) {
+      return false;
+    }
+
+    typeName = assembly == null
+      ? name.Substring(0, dot)
+      : $"{name.Substring(0, dot)}, {assembly}";
+    memberName = name.Substring(dot + 1);
+
+    return true;
+  }
+
+  /// <summary>
+  /// Finds a type by its, optionally assembly qualified, name.
+  /// </summary>
+  /// <remarks>
+  /// Names that are not assembly qualified are looked up in every assembly loaded into the
+  /// current domain.
+  /// </remarks>
+  public static Type? FindType(string name) {
+    var type = Type.GetType(name);
+    if (type != null || name.Contains(',')) {
+      return type;
+    }
+
+    return AppDomain.CurrentDomain
+      .GetAssemblies()
+      .Select(assembly => assembly.GetType(name))
+      .FirstOrDefault(found => found != null);
+  }
+
+  /// <summary>
+  /// Finds the single overload of `name` on `type` whose parameters and return type match
+  /// the supplied `TypeId`s.
+  /// </summary>
+  public static MethodInfo? FindMethod(
+    Type type,
+    string name,
+    BindingFlags flags,
+    TypeId[] args,
+    TypeId ret,
+    out BridgeError error
+  ) {
+    var methods = type
+      .GetMethods(flags)
+      .Where(method => method.Name == name)
+      .ToArray();
+
+    var matches = MostSpecific(methods.Where(method => Matches(method, args, ret)), args);
+
+    switch (matches.Length) {
+      case 1:
+        error = default;
+        return matches[0];
+      case 0:
+        error = BridgeError.MethodNotFound;
+        return null;
+      default:
+        error = BridgeError.AmbiguousMatch;
+        return null;
+    }
+  }
+
+  /// <summary>
+  /// Finds a field or property accessor, a method or a constructor on `type` matching the
+  /// supplied `TypeId`s.
+  /// </summary>
+  /// <remarks>
+  /// Instance members can be qualified with the full name of the interface declaring them,
+  /// e.g. `System.Collections.IEnumerator.MoveNext`, which also reaches explicit interface
+  /// implementations.  Generic interfaces are named by their definition, e.g.
+  /// `System.Collections.Generic.IEnumerator`1.Current`.
+  /// </remarks>
+  public static Thunk? FindMember(
+    Type type,
+    string name,
+    MemberKind kind,
+    BindingFlags flags,
+    TypeId[] args,
+    TypeId ret,
+    out BridgeError error
+  ) {
+    var dot = name.LastIndexOf('.');
+    if (dot > 0 && flags.HasFlag(BindingFlags.Instance)) {
+      return FindInterfaceMember(
+        type,
+        name.Substring(0, dot),
+        name.Substring(dot + 1),
+        kind,
+        args,
+        ret,
+        out error
+      );
+    }
+
+    var isGetter = args.Length == 0;
+    var isSetter = args.Length == 1 && ret.Kind == TypeKind.Void;
+
+    error = default;
+
+    switch (kind) {
+      case MemberKind.Field:
+        var field = type.GetField(name, flags);
+        if (field == null) {
+          error = BridgeError.MemberNotFound;
+        } else if (isGetter && ret.Matches(field.FieldType)) {
+          return Thunk.ForFieldGetter(field, ret);
+        } else if (isSetter && !field.IsInitOnly && args[0].Matches(field.FieldType)) {
+          return Thunk.ForFieldSetter(field, args[0]);
+        } else {
+          error = BridgeError.TypeMismatch;
+        }
+
+        return null;
+      case MemberKind.Property:
+        var property = type
+          .GetProperties(flags)
+          .FirstOrDefault(candidate =>
+            candidate.Name == name && candidate.GetIndexParameters().Length == 0
+          );
+
+        var accessor = isGetter ? property?.GetMethod : property?.SetMethod;
+        if (property == null || accessor == null || !accessor.IsPublic) {
+          error = BridgeError.MemberNotFound;
+        } else if (isGetter && ret.Matches(property.PropertyType)) {
+          return Thunk.ForMethod(accessor, args, ret);
+        } else if (isSetter && args[0].Matches(property.PropertyType)) {
+          return Thunk.ForMethod(accessor, args, ret);
+        } else {
+          error = BridgeError.TypeMismatch;
+        }
+
+        return null;
+      case MemberKind.Method:
+        var method = FindMethod(type, name, flags, args, ret, out error);
+        var isInstance = flags.HasFlag(BindingFlags.Instance);
+        if (method == null && error == BridgeError.MethodNotFound && isInstance) {
+          method = FindInterfaceMethod(type, name, args, ret, out error);
+        }
+
+        return method == null ? null : Thunk.ForMethod(method, args, ret);
+      case MemberKind.Constructor:
+        var ctor = FindConstructor(type, args, ret, out error);
+
+        return ctor == null ? null : Thunk.ForConstructor(ctor, args, ret);
+      default:
+        error = BridgeError.MemberNotFound;
+        return null;
+    }
+  }
+
+  /// <summary>
+  /// Finds a member of the single interface named `interfaceName` implemented by `type`
+  /// whose member matches the supplied `TypeId`s.
+  /// </summary>
+  static Thunk? FindInterfaceMember(
+    Type type,
+    string interfaceName,
+    string name,
+    MemberKind kind,
+    TypeId[] args,
+    TypeId ret,
+    out BridgeError error
+  ) {
+    var interfaces = type
+      .GetInterfaces()
+      .Where(iface => InterfaceName(iface) == interfaceName)
+      .ToArray();
+
+    error = BridgeError.MemberNotFound;
+
+    var matches = new List<Thunk>();
+    foreach (var iface in interfaces) {
+      var flags = BindingFlags.Public | BindingFlags.Instance;
+      var thunk = FindMember(iface, name, kind, flags, args, ret, out var candidateError);
+      if (thunk != null) {
+        matches.Add(thunk);
+      } else if (matches.Count == 0) {
+        error = candidateError;
+      }
+    }
+
+    switch (matches.Count) {
+      case 1:
+        error = default;
+        return matches[0];
+      case 0:
+        return null;
+      default:
+        error = BridgeError.AmbiguousMatch;
+        return null;
+    }
+  }
+
+  static string? InterfaceName(Type iface) {
+    return iface.IsGenericType ? iface.GetGenericTypeDefinition().FullName : iface.FullName;
+  }
+
+  /// <summary>
+  /// Finds a method through the interfaces `type` implements, this is the only way to reach
+  /// explicit interface implementations.
+  /// </summary>
+  /// <remarks>
+  /// Invoking an interface method dispatches to the implementation of the target object.
+  /// </remarks>
+  static MethodInfo? FindInterfaceMethod(
+    Type type,
+    string name,
+    TypeId[] args,
+    TypeId ret,
+    out BridgeError error
+  ) {
+    var candidates = type
+      .GetInterfaces()
+      .SelectMany(iface => iface.GetMethods())
+      .Where(method => method.Name == name && Matches(method, args, ret));
+    var matches = MostSpecific(candidates, args);
+
+    switch (matches.Length) {
+      case 1:
+        error = default;
+        return matches[0];
+      case 0:
+        error = BridgeError.MethodNotFound;
+        return null;
+      default:
+        error = BridgeError.AmbiguousMatch;
+        return null;
+    }
+  }
+
+  /// <summary>
+  /// Finds the public constructor of `type` whose parameters match the supplied `TypeId`s.
+  /// </summary>
+  static ConstructorInfo? FindConstructor(
+    Type type,
+    TypeId[] args,
+    TypeId ret,
+    out BridgeError error
+  ) {
+    if (ret.Kind != TypeKind.Object) {
+      error = BridgeError.TypeMismatch;
+      return null;
+    }
+
+    error = BridgeError.MemberNotFound;
+    if (type.IsAbstract || type.ContainsGenericParameters) {
+      return null;
+    }
+
+    var candidates = type
+      .GetConstructors(BindingFlags.Public | BindingFlags.Instance)
+      .Where(ctor => Matches(ctor, args));
+    var matches = MostSpecific(candidates, args);
+
+    switch (matches.Length) {
+      case 1:
+        error = default;
+        return matches[0];
+      case 0:
+        return null;
+      default:
+        error = BridgeError.AmbiguousMatch;
+        return null;
+    }
+  }
+
+  /// <summary>
+  /// Narrows matching overloads to at most two of those with the fewest parameters only
+  /// matched through the `Object` fallback, so an object argument picks `(object)` over
+  /// `(string)` rather than being ambiguous.
+  /// </summary>
+  static T[] MostSpecific<T>(IEnumerable<T> matches, TypeId[] args) where T : MethodBase {
+    return matches
+      .GroupBy(method => Fallbacks(method, args))
+      .OrderBy(group => group.Key)
+      .Select(group => group.Take(2).ToArray())
+      .FirstOrDefault() ?? new T[0];
+  }
+
+  static int Fallbacks(MethodBase method, TypeId[] args) {
+    var parameters = method.GetParameters();
+    var count = 0;
+    for (var i = 0; i < args.Length; i++) {
+      if (!args[i].MatchesExactly(parameters[i].ParameterType)) {
+        count++;
+      }
+    }
+
+    return count;
+  }
+
+  static bool Matches(MethodInfo method, TypeId[] args, TypeId ret) {
+    return !method.IsGenericMethodDefinition
+      && Matches(method, args)
+      && ret.Matches(method.ReturnType);
+  }
+
+  static bool Matches(MethodBase method, TypeId[] args) {
+    var parameters = method.GetParameters();
+    if (parameters.Length != args.Length) {
+      return false;
+    }
+
+    for (var i = 0; i < args.Length; i++) {
+      if (!args[i].Matches(parameters[i].ParameterType)) {
+        return false;
+      }
+    }
+
+    return true;
+  }
+}
@@ -0,0 +1,103 @@
+using System;
+using System.Linq;
+using System.Reflection;
+using System.Runtime.InteropServices;
+
+[UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+public unsafe delegate IntPtr ThunkDelegate(IntPtr self, void** args, void* ret);
+
+/// <summary>
+/// Native callable wrapper around a resolved managed member.
+/// </summary>
+/// <remarks>
+/// Every thunk shares the same native signature, `self` is the handle of the target object
+/// or zero for static members, arguments are passed as an array of pointers to their native
+/// representation and the return value is written to `ret`.  Exceptions never cross into
+/// rust, the thunk returns a handle to the thrown exception instead or zero on success.
+/// </remarks>
+public sealed unsafe class Thunk {
+  readonly Func<object?, object?[], object?> Target;
+  readonly TypeId[] Args;
+  readonly Type[] ArgTypes;
+  readonly TypeId Ret;
+
+  // Keeps the delegate backing `Pointer` alive for as long as the thunk is alive
+  readonly ThunkDelegate Delegate;
+
+  public readonly IntPtr Pointer;
+
+  public Thunk(
+    Func<object?, object?[], object?> target,
+    TypeId[] args,
+    Type[] argTypes,
+    TypeId ret
+  ) {
+    Target = target;
+    Args = args;
+    ArgTypes = argTypes;
+    Ret = ret;
+    Delegate = Invoke;
+    Pointer = Marshal.GetFunctionPointerForDelegate(Delegate);
+  }
+
+  public static Thunk ForMethod(MethodInfo method, TypeId[] args, TypeId ret) {
+    return new Thunk(
+      (self, values) => method.Invoke(
+        self,
+        BindingFlags.DoNotWrapExceptions,
+        null,
+        values,
+        null
+      ),
+      args,
+      ParameterTypes(method),
+      ret
+    );
+  }
+
+  public static Thunk ForConstructor(ConstructorInfo ctor, TypeId[] args, TypeId ret) {
+    return new Thunk(
+      (_, values) => ctor.Invoke(BindingFlags.DoNotWrapExceptions, null, values, null),
+      args,
+      ParameterTypes(ctor),
+      ret
+    );
+  }
+
+  public static Thunk ForFieldGetter(FieldInfo field, TypeId ret) {
+    return new Thunk((self, _) => field.GetValue(self), new TypeId[0], new Type[0], ret);
+  }
+
+  public static Thunk ForFieldSetter(FieldInfo field, TypeId value) {
+    return new Thunk(
+      (self, values) => {
+        field.SetValue(self, values[0]);
+        return null;
+      },
+      new[] { value },
+      new[] { field.FieldType },
+      new TypeId(TypeKind.Void)
+    );
+  }
+
+  static Type[] ParameterTypes(MethodBase method) {
+    return method.GetParameters().Select(parameter => parameter.ParameterType).ToArray();
+  }
+
+  IntPtr Invoke(IntPtr self, void** args, void* ret) {
+    try {
+      var values = new object?[Args.Length];
+      for (var i = 0; i < values.Length; i++) {
+        values[i] = Args[i].Read(args[i], ArgTypes[i]);
+      }
+
+      var value = Target(Handles.Target(self), values);
+
+      Ret.Write(ret, value);
+
+      return IntPtr.Zero;
+    } catch (Exception e) {
+      return Handles.Alloc(e);
+    }
+  }
+}
@@ -0,0 +1,386 @@
+using System;
+using System.Collections.Generic;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+
+/// <summary>
+/// Mirrors the discriminants of the rust `TypeId` enum, order matters.
+/// </summary>
+public enum TypeKind : byte {
+  Char,
+  Byte,
+  Int16,
+  Int32,
+  Int64,
+
+  SByte,
+  UInt16,
+  UInt32,
+  UInt64,
+
+  Float,
+  Double,
+  String,
+  Boolean,
+
+  Object,
+
+  Array,
+  Nullable,
+  Enumerable,
+
+  Void,
+}
+
+/// <summary>
+/// Managed copy of a rust `TypeId`.
+/// </summary>
+/// <remarks>
+/// The rust side is declared as `#[repr(C, u8)]` so each value is laid out as a `u8` tag
+/// followed by a pointer sized payload holding the boxed inner `TypeId`, if any.
+/// </remarks>
+public sealed unsafe class TypeId {
+  public static readonly int Size = 2 * IntPtr.Size;
+
+  public readonly TypeKind Kind;
+  public readonly TypeId? Inner;
+
+  public TypeId(TypeKind kind, TypeId? inner = null) {
+    Kind = kind;
+    Inner = inner;
+  }
+
+  public static TypeId Read(byte* ptr) {
+    var kind = (TypeKind)(*ptr);
+    switch (kind) {
+      case TypeKind.Array:
+      case TypeKind.Nullable:
+      case TypeKind.Enumerable:
+        return new TypeId(kind, Read(*(byte**)(ptr + IntPtr.Size)));
+      default:
+        return new TypeId(kind);
+    }
+  }
+
+  public static TypeId[] ReadMany(byte* ptr, int length) {
+    var ids = new TypeId[length];
+    for (var i = 0; i < length; i++) {
+      ids[i] = Read(ptr + i * Size);
+    }
+
+    return ids;
+  }
+
+  /// <summary>
+  /// Determines if a managed type can be represented by this `TypeId`.
+  /// </summary>
+  public bool Matches(Type type) {
+    switch (Kind) {
+      case TypeKind.Char: return type == typeof(char);
+      case TypeKind.Byte: return type == typeof(byte);
+      case TypeKind.Int16: return type == typeof(short);
+      case TypeKind.Int32: return type == typeof(int);
+      case TypeKind.Int64: return type == typeof(long);
+      case TypeKind.SByte: return type == typeof(sbyte);
+      case TypeKind.UInt16: return type == typeof(ushort);
+      case TypeKind.UInt32: return type == typeof(uint);
+      case TypeKind.UInt64: return type == typeof(ulong);
+      case TypeKind.Float: return type == typeof(float);
+      case TypeKind.Double: return type == typeof(double);
+      case TypeKind.String: return type == typeof(string);
+      case TypeKind.Boolean: return type == typeof(bool);
+      case TypeKind.Object: return type.IsClass || type.IsInterface;
+      case TypeKind.Void: return type == typeof(void);
+      case TypeKind.Array:
+        return type.IsArray && type.GetArrayRank() == 1 && Inner!.Matches(type.GetElementType()!);
+      case TypeKind.Nullable:
+        var underlying = Nullable.GetUnderlyingType(type);
+        return underlying != null && Inner!.Matches(underlying);
+      case TypeKind.Enumerable:
+        return type.IsGenericType
+          && type.GetGenericTypeDefinition() == typeof(IEnumerable<>)
+          && Inner!.Matches(type.GetGenericArguments()[0]);
+      default:
+        return false;
+    }
+  }
+
+  /// <summary>
+  /// Determines if a managed type is represented by this `TypeId` itself rather than only
+  /// through the `Object` fallback, which also accepts strings, arrays and enumerables.
+  /// </summary>
+  public bool MatchesExactly(Type type) {
+    switch (Kind) {
+      case TypeKind.Object:
+        return Matches(type)
+          && type != typeof(string)
+          && !type.IsArray
+          && !(type.IsGenericType && type.GetGenericTypeDefinition() == typeof(IEnumerable<>));
+      case TypeKind.Array:
+        return Matches(type) && Inner!.MatchesExactly(type.GetElementType()!);
+      case TypeKind.Nullable:
+        return Matches(type) && Inner!.MatchesExactly(Nullable.GetUnderlyingType(type)!);
+      case TypeKind.Enumerable:
+        return Matches(type) && Inner!.MatchesExactly(type.GetGenericArguments()[0]);
+      default:
+        return Matches(type);
+    }
+  }
+
+  /// <summary>
+  /// Size in bytes of the native representation of this type.
+  /// </summary>
+  public int NativeSize {
+    get {
+      switch (Kind) {
+        case TypeKind.Byte:
+        case TypeKind.SByte:
+        case TypeKind.Boolean:
+          return 1;
+        case TypeKind.Char:
+        case TypeKind.Int16:
+        case TypeKind.UInt16:
+          return 2;
+        case TypeKind.Int32:
+        case TypeKind.UInt32:
+        case TypeKind.Float:
+          return 4;
+        case TypeKind.Int64:
+        case TypeKind.UInt64:
+        case TypeKind.Double:
+          return 8;
+        case TypeKind.Object: return IntPtr.Size;
+        case TypeKind.String:
+        case TypeKind.Array:
+          return sizeof(RawArray);
+        case TypeKind.Nullable:
+          // `HasValue` followed by the value, padded to the alignment of the value
+          var align = Inner!.NativeAlignment;
+          return (align + Inner.NativeSize + align - 1) / align * align;
+        default:
+          throw new NotSupportedException($"Marshalling `{this}` is not supported");
+      }
+    }
+  }
+
+  /// <summary>
+  /// Alignment in bytes of the native representation of this type.
+  /// </summary>
+  public int NativeAlignment {
+    get {
+      switch (Kind) {
+        case TypeKind.Nullable: return Inner!.NativeAlignment;
+        case TypeKind.String:
+        case TypeKind.Array:
+          return IntPtr.Size;
+        default: return Math.Min(NativeSize, IntPtr.Size);
+      }
+    }
+  }
+
+  /// <summary>
+  /// Reads the native representation found at `ptr` into a managed value of `type`.
+  /// </summary>
+  /// <remarks>
+  /// Enumerables are read from a <see cref="RawIterator"/> but written as object handles.
+  /// </remarks>
+  public object? Read(void* ptr, Type type) {
+    switch (Kind) {
+      case TypeKind.Char: return *(char*)ptr;
+      case TypeKind.Byte: return *(byte*)ptr;
+      case TypeKind.Int16: return *(short*)ptr;
+      case TypeKind.Int32: return *(int*)ptr;
+      case TypeKind.Int64: return *(long*)ptr;
+      case TypeKind.SByte: return *(sbyte*)ptr;
+      case TypeKind.UInt16: return *(ushort*)ptr;
+      case TypeKind.UInt32: return *(uint*)ptr;
+      case TypeKind.UInt64: return *(ulong*)ptr;
+      case TypeKind.Float: return *(float*)ptr;
+      case TypeKind.Double: return *(double*)ptr;
+      case TypeKind.Boolean: return *(byte*)ptr != 0;
+      case TypeKind.Object: return Handles.Target(*(IntPtr*)ptr);
+      case TypeKind.String: return ((RawArray*)ptr)->ToManagedString();
+      case TypeKind.Array:
+        return ((RawArray*)ptr)->ToArray(Inner!, type.GetElementType()!);
+      case TypeKind.Enumerable:
+        return ((RawIterator*)ptr)->ToEnumerable(Inner!, type.GetGenericArguments()[0]);
+      case TypeKind.Nullable:
+        var underlying = Nullable.GetUnderlyingType(type) ?? type;
+        return *(byte*)ptr == 0
+          ? null
+          : Inner!.Read((byte*)ptr + Inner.NativeAlignment, underlying);
+      default:
+        throw new NotSupportedException($"Marshalling `{this}` is not supported");
+    }
+  }
+
+  /// <summary>
+  /// Writes the native representation of `value` to `ptr`.
+  /// </summary>
+  public void Write(void* ptr, object? value) {
+    switch (Kind) {
+      case TypeKind.Void: break;
+      case TypeKind.Char: *(char*)ptr = (char)value!; break;
+      case TypeKind.Byte: *(byte*)ptr = (byte)value!; break;
+      case TypeKind.Int16: *(short*)ptr = (short)value!; break;
+      case TypeKind.Int32: *(int*)ptr = (int)value!; break;
+      case TypeKind.Int64: *(long*)ptr = (long)value!; break;
+      case TypeKind.SByte: *(sbyte*)ptr = (sbyte)value!; break;
+      case TypeKind.UInt16: *(ushort*)ptr = (ushort)value!; break;
+      case TypeKind.UInt32: *(uint*)ptr = (uint)value!; break;
+      case TypeKind.UInt64: *(ulong*)ptr = (ulong)value!; break;
+      case TypeKind.Float: *(float*)ptr = (float)value!; break;
+      case TypeKind.Double: *(double*)ptr = (double)value!; break;
+      case TypeKind.Boolean: *(byte*)ptr = (bool)value! ? (byte)1 : (byte)0; break;
+      case TypeKind.Object:
+      case TypeKind.Enumerable:
+        *(IntPtr*)ptr = Handles.Alloc(value);
+        break;
+      case TypeKind.String: *(RawArray*)ptr = RawArray.From((string?)value); break;
+      case TypeKind.Array:
+        *(RawArray*)ptr = RawArray.From((Array?)value, Inner!);
+        break;
+      case TypeKind.Nullable:
+        *(byte*)ptr = value == null ? (byte)0 : (byte)1;
+        if (value != null) {
+          Inner!.Write((byte*)ptr + Inner.NativeAlignment, value);
+        }
+        break;
+      default:
+        throw new NotSupportedException($"Marshalling `{this}` is not supported");
+    }
+  }
+
+  public override string ToString() {
+    return Inner == null ? Kind.ToString() : $"{Kind}<{Inner}>";
+  }
+}
+
+/// <summary>
+/// Native representation of the rust `RawArray` marshal type, also used for strings as
+/// arrays of UTF-16 code units.
+/// </summary>
+/// <remarks>
+/// Whoever allocates the buffer fills in `Release`, the receiver of an array passes `Handle`
+/// to it once done.  Arrays received from rust are only borrowed for the duration of a call
+/// and are copied, arrays handed to rust are owned by rust.  A null `Ptr` is a null
+/// reference.
+/// </remarks>
+[StructLayout(LayoutKind.Sequential)]
+public unsafe struct RawArray {
+  public byte* Ptr;
+  public uint Length;
+  public IntPtr Handle;
+  public delegate* unmanaged[Cdecl]<IntPtr, void> Release;
+
+  /// <summary>
+  /// Creates the native representation of `array` whose elements are marshalled as
+  /// `element`.
+  /// </summary>
+  /// <remarks>
+  /// Arrays of primitives already have the native layout and are pinned so rust can read
+  /// them in place, other arrays are copied into a native buffer.
+  /// </remarks>
+  public static RawArray From(Array? array, TypeId element) {
+    if (array == null) {
+      return default;
+    }
+
+    if (array.GetType().GetElementType()!.IsPrimitive) {
+      var handle = Handles.Alloc(array, GCHandleType.Pinned);
+
+      return new RawArray {
+        Ptr = (byte*)GCHandle.FromIntPtr(handle).AddrOfPinnedObject(),
+        Length = (uint)array.Length,
+        Handle = handle,
+        Release = &ReleasePinned,
+      };
+    }
+
+    var size = element.NativeSize;
+    var buf = (byte*)Marshal.AllocCoTaskMem(Math.Max(array.Length * size, 1));
+    for (var i = 0; i < array.Length; i++) {
+      element.Write(buf + i * size, array.GetValue(i));
+    }
+
+    return new RawArray {
+      Ptr = buf,
+      Length = (uint)array.Length,
+      Handle = (IntPtr)buf,
+      Release = &ReleaseBuffer,
+    };
+  }
+
+  /// <summary>
+  /// Creates the native representation of `str`, the string is pinned so rust can read it
+  /// in place.
+  /// </summary>
+  public static RawArray From(string? str) {
+    if (str == null) {
+      return default;
+    }
+
+    var handle = Handles.Alloc(str, GCHandleType.Pinned);
+
+    return new RawArray {
+      Ptr = (byte*)GCHandle.FromIntPtr(handle).AddrOfPinnedObject(),
+      Length = (uint)str.Length,
+      Handle = handle,
+      Release = &ReleasePinned,
+    };
+  }
+
+  /// <summary>
+  /// Copies the UTF-16 code units into a new string, returns null for a null reference.
+  /// </summary>
+  public string? ToManagedString() {
+    return Ptr == null ? null : new string((char*)Ptr, 0, (int)Length);
+  }
+
+  /// <summary>
+  /// Copies the elements into a new managed array of `elementType`, returns null for a null
+  /// reference.
+  /// </summary>
+  public Array? ToArray(TypeId element, Type elementType) {
+    if (Ptr == null) {
+      return null;
+    }
+
+    var array = Array.CreateInstance(elementType, (int)Length);
+    var size = element.NativeSize;
+
+    if (elementType.IsPrimitive) {
+      var bytes = (long)Length * size;
+      var handle = GCHandle.Alloc(array, GCHandleType.Pinned);
+      try {
+        Buffer.MemoryCopy(Ptr, (void*)handle.AddrOfPinnedObject(), bytes, bytes);
+      } finally {
+        handle.Free();
+      }
+
+      return array;
+    }
+
+    for (var i = 0; i < array.Length; i++) {
+      array.SetValue(element.Read(Ptr + i * size, elementType), i);
+    }
+
+    return array;
+  }
+
+  /// <summary>
+  /// Unpins an array or string handed to rust by <see cref="From"/>.
+  /// </summary>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  static void ReleasePinned(IntPtr handle) {
+    Handles.Free(handle);
+  }
+
+  /// <summary>
+  /// Frees a buffer handed to rust by <see cref="From"/>, the elements are owned by rust.
+  /// </summary>
+  [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+  static void ReleaseBuffer(IntPtr handle) {
+    Marshal.FreeCoTaskMem(handle);
+  }
+}
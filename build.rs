@@ -0,0 +1,81 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+
+/// Path to a prebuilt bridge assembly, used instead of building it with the .NET SDK
+const PREBUILT_ENV: &str = "DOTNET_BRIDGE_DLL";
+
+/// Builds the bridge assembly into `$OUT_DIR/bridge` so it can be embedded using
+/// `include_bytes!` and extracted at runtime.
+///
+/// Setting `DOTNET_BRIDGE_DLL` to a prebuilt `bridge.dll` skips the build, so the .NET SDK
+/// is only needed to build the bridge itself.
+fn main() {
+  println!("cargo:rerun-if-changed=bridge/src");
+  println!("cargo:rerun-if-changed=bridge/bridge.csproj");
+  println!("cargo:rerun-if-changed=bridge/bridge.runtimeconfig.json");
+  println!("cargo:rerun-if-env-changed={}", PREBUILT_ENV);
+
+  let out = env::var("OUT_DIR").expect("`$OUT_DIR` not defined.");
+  let out = PathBuf::from(out);
+  let bridge = out.join("bridge");
+
+  if let Some(prebuilt) = env::var_os(PREBUILT_ENV) {
+    println!("cargo:rerun-if-changed={}", Path::new(&prebuilt).display());
+
+    copy_prebuilt(Path::new(&prebuilt), &bridge);
+    return;
+  }
+
+  // Intermediate files go to `$OUT_DIR` as well, the source tree is left untouched
+  let obj = out.join("obj");
+  let status = Command::new("dotnet")
+    .args(&[
+      "build",
+      "bridge/bridge.csproj",
+      "--configuration",
+      "Release",
+      "--output",
+    ])
+    .arg(&bridge)
+    .arg(format!(
+      "-p:BaseIntermediateOutputPath={}{}",
+      obj.display(),
+      std::path::MAIN_SEPARATOR
+    ))
+    .status();
+
+  match status {
+    Ok(status) if status.success() => {}
+    Ok(status) => fail(&format!(
+      "Failed to build bridge assembly, `dotnet build` exited with {}",
+      status
+    )),
+    Err(err) => fail(&format!(
+      "Failed to run `dotnet build` ({}), install the .NET SDK or set `{}` to a \
+       prebuilt bridge.dll",
+      err, PREBUILT_ENV
+    )),
+  }
+}
+
+fn copy_prebuilt(prebuilt: &Path, bridge: &Path) {
+  let copied = fs::create_dir_all(bridge)
+    .and_then(|_| fs::copy(prebuilt, bridge.join("bridge.dll")));
+
+  if let Err(err) = copied {
+    fail(&format!(
+      "Failed to copy prebuilt bridge assembly `{}` from `{}`: {}",
+      prebuilt.display(),
+      PREBUILT_ENV,
+      err
+    ));
+  }
+}
+
+/// Report `message` as a build error without a panic backtrace
+fn fail(message: &str) -> ! {
+  eprintln!("error: {}", message);
+  process::exit(1);
+}
@@ -0,0 +1,78 @@
+//! Primary and secondary host contexts.
+use crate::{HostFxrError, HostFxrResult};
+
+/// `Success_HostAlreadyInitialized`
+const HOST_ALREADY_INITIALIZED: i32 = 0x00000001;
+/// `Success_DifferentRuntimeProperties`
+const DIFFERENT_RUNTIME_PROPERTIES: i32 = 0x00000002;
+
+/// Whether a host context loads the runtime or attaches to the one already running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostFxrContextKind {
+  /// The first context of the process, loads the runtime and can set runtime properties
+  Primary,
+  /// Created after the runtime was loaded, the runtime config was validated against the
+  /// running runtime and properties can only be read
+  Secondary(Compatibility),
+}
+
+/// Compatibility of the runtime config of a secondary context with the running runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+  /// The frameworks are compatible and no runtime property differs
+  Compatible,
+  /// The frameworks are compatible but some runtime properties differ from the running
+  /// runtime, see [`HostFxr::runtime_property_diffs`](crate::HostFxr::runtime_property_diffs)
+  DifferentRuntimeProperties,
+}
+
+impl HostFxrContextKind {
+  /// Get the context kind from the status returned by the initialize functions
+  pub(crate) fn from_status(status: i32) -> HostFxrResult<Self> {
+    match status {
+      HOST_ALREADY_INITIALIZED => Ok(Self::Secondary(Compatibility::Compatible)),
+      DIFFERENT_RUNTIME_PROPERTIES => {
+        Ok(Self::Secondary(Compatibility::DifferentRuntimeProperties))
+      }
+      status => HostFxrError::from_status(status).map(|_| Self::Primary),
+    }
+  }
+
+  pub fn is_primary(self) -> bool {
+    self == Self::Primary
+  }
+}
+
+/// A runtime property requested by a secondary context that differs from the running
+/// runtime
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyDiff {
+  pub name: String,
+  /// Value requested by the runtime config of the secondary context
+  pub requested: String,
+  /// Value of the running runtime, `None` if the property is not set
+  pub active: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Compatibility, HostFxrContextKind};
+
+  #[test]
+  fn test_from_status() {
+    let cases = vec![
+      (0, HostFxrContextKind::Primary),
+      (1, HostFxrContextKind::Secondary(Compatibility::Compatible)),
+      (
+        2,
+        HostFxrContextKind::Secondary(Compatibility::DifferentRuntimeProperties),
+      ),
+    ];
+
+    for (status, expected) in cases {
+      assert_eq!(HostFxrContextKind::from_status(status).unwrap(), expected);
+    }
+
+    assert!(HostFxrContextKind::from_status(0x80008081u32 as i32).is_err());
+  }
+}
@@ -1,13 +1,27 @@
-use dotnet_hostfxr_sys::{
-  char_t, hostfxr_delegate_type,
-  hostfxr_delegate_type_hdt_load_assembly_and_get_function_pointer,
-};
-use std::{ffi::c_void, mem::MaybeUninit, os::raw::c_int, ptr::null_mut};
-
 use crate::{
+  function::FunctionPointer,
   string::{IntoFxrBytes, IntoFxrPtr},
   HostFxrError, HostFxrResult,
 };
+use dotnet_hostfxr_sys::{
+  char_t, hostfxr_delegate_type, hostfxr_delegate_type_hdt_get_function_pointer,
+  hostfxr_delegate_type_hdt_load_assembly_and_get_function_pointer,
+};
+use std::{
+  ffi::c_void,
+  os::raw::c_int,
+  ptr::{null, null_mut, NonNull},
+};
+
+/// `hdt_load_assembly`, added in .NET 8 and missing from the bundled headers
+pub const HOSTFXR_DELEGATE_TYPE_HDT_LOAD_ASSEMBLY: hostfxr_delegate_type = 7;
+
+/// `hdt_load_assembly_bytes`, added in .NET 8 and missing from the bundled headers
+pub const HOSTFXR_DELEGATE_TYPE_HDT_LOAD_ASSEMBLY_BYTES: hostfxr_delegate_type = 8;
+
+/// Delegate type name sentinel resolving a method marked `[UnmanagedCallersOnly]`, the
+/// returned pointer calls the method directly without a delegate marshalling stub
+pub const UNMANAGEDCALLERSONLY_METHOD: *const char_t = usize::MAX as *const char_t;
 
 pub trait RuntimeDelegate {
   type Fn;
@@ -54,28 +68,69 @@ impl LoadAssemblyAndGetFunctionPointerDelegate {
     delegate_type_name: D,
   ) -> HostFxrResult<F>
   where
+    F: FunctionPointer,
     A: IntoFxrBytes<char_t>,
     T: IntoFxrBytes<char_t>,
     M: IntoFxrBytes<char_t>,
     D: IntoFxrBytes<char_t>,
   {
-    let mut delegate = MaybeUninit::<F>::zeroed();
-    let delegate_ptr = delegate.as_mut_ptr() as *mut _ as *mut *mut _;
+    let delegate_type_name = unsafe { delegate_type_name.into_fxr_ptr() };
+    self.invoke_raw(assembly_path, type_name, method_name, delegate_type_name)
+  }
+
+  /// Same as [`invoke`](Self::invoke) but resolves a method marked
+  /// `[UnmanagedCallersOnly]`, no delegate type is needed.
+  pub fn invoke_unmanaged_callers_only<F, A, T, M>(
+    &self,
+    assembly_path: A,
+    type_name: T,
+    method_name: M,
+  ) -> HostFxrResult<F>
+  where
+    F: FunctionPointer,
+    A: IntoFxrBytes<char_t>,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+  {
+    self.invoke_raw(
+      assembly_path,
+      type_name,
+      method_name,
+      UNMANAGEDCALLERSONLY_METHOD,
+    )
+  }
+
+  fn invoke_raw<F, A, T, M>(
+    &self,
+    assembly_path: A,
+    type_name: T,
+    method_name: M,
+    delegate_type_name: *const char_t,
+  ) -> HostFxrResult<F>
+  where
+    F: FunctionPointer,
+    A: IntoFxrBytes<char_t>,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+  {
+    let mut delegate = null_mut();
 
     let flag = unsafe {
       self.0(
         assembly_path.into_fxr_ptr(),
         type_name.into_fxr_ptr(),
         method_name.into_fxr_ptr(),
-        delegate_type_name.into_fxr_ptr(),
+        delegate_type_name,
         null_mut(),
-        delegate_ptr,
+        &mut delegate,
       )
     };
 
     HostFxrError::from_status(flag)?;
 
-    Ok(unsafe { delegate.assume_init() })
+    let delegate = NonNull::new(delegate).ok_or(HostFxrError::NullFunctionPointer)?;
+
+    Ok(unsafe { F::from_ptr(delegate) })
   }
 }
 
@@ -88,3 +143,219 @@ impl RuntimeDelegate for LoadAssemblyAndGetFunctionPointerDelegate {
     Self(native)
   }
 }
+
+type GetFunctionPointerFn = unsafe extern "C" fn(
+  type_name: *const char_t,
+  method_name: *const char_t,
+  delegate_type_name: *const char_t,
+  load_context: *mut c_void,
+  reserved: *mut c_void,
+  delegate: *mut *mut c_void,
+) -> c_int;
+
+#[derive(Debug, Clone, Copy)]
+pub struct GetFunctionPointerDelegate(GetFunctionPointerFn);
+
+impl GetFunctionPointerDelegate {
+  /// Calling this function will find the specified type and method in an assembly that was
+  /// already loaded into the default `AssemblyLoadContext` and return a native function
+  /// pointer to that method.
+  ///
+  /// # Arguments
+  ///  * `type_name` - Assembly qualified type name to find
+  ///  * `method_name` - Name of the method on the `type_name` to find. The method must be
+  ///    `static` and must match the signature of `delegate_type_name`.
+  ///  * `delegate_type_name` - Assembly qualified delegate type name for the method
+  ///    signature.
+  pub fn invoke<F, T, M, D>(
+    &self,
+    type_name: T,
+    method_name: M,
+    delegate_type_name: D,
+  ) -> HostFxrResult<F>
+  where
+    F: FunctionPointer,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+    D: IntoFxrBytes<char_t>,
+  {
+    let delegate_type_name = unsafe { delegate_type_name.into_fxr_ptr() };
+    self.invoke_raw(type_name, method_name, delegate_type_name)
+  }
+
+  /// Same as [`invoke`](Self::invoke) but resolves a method marked
+  /// `[UnmanagedCallersOnly]`, no delegate type is needed.
+  pub fn invoke_unmanaged_callers_only<F, T, M>(
+    &self,
+    type_name: T,
+    method_name: M,
+  ) -> HostFxrResult<F>
+  where
+    F: FunctionPointer,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+  {
+    self.invoke_raw(type_name, method_name, UNMANAGEDCALLERSONLY_METHOD)
+  }
+
+  fn invoke_raw<F, T, M>(
+    &self,
+    type_name: T,
+    method_name: M,
+    delegate_type_name: *const char_t,
+  ) -> HostFxrResult<F>
+  where
+    F: FunctionPointer,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+  {
+    let mut delegate = null_mut();
+
+    let flag = unsafe {
+      self.0(
+        type_name.into_fxr_ptr(),
+        method_name.into_fxr_ptr(),
+        delegate_type_name,
+        null_mut(),
+        null_mut(),
+        &mut delegate,
+      )
+    };
+
+    HostFxrError::from_status(flag)?;
+
+    let delegate = NonNull::new(delegate).ok_or(HostFxrError::NullFunctionPointer)?;
+
+    Ok(unsafe { F::from_ptr(delegate) })
+  }
+}
+
+impl RuntimeDelegate for GetFunctionPointerDelegate {
+  type Fn = GetFunctionPointerFn;
+  const KIND: hostfxr_delegate_type = hostfxr_delegate_type_hdt_get_function_pointer;
+
+  fn from_native(native: Self::Fn) -> Self {
+    Self(native)
+  }
+}
+
+#[cfg(windows)]
+type LoadInMemoryAssemblyFn =
+  unsafe extern "C" fn(module: *mut c_void, assembly_path: *const char_t) -> c_int;
+
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy)]
+pub struct LoadInMemoryAssemblyDelegate(LoadInMemoryAssemblyFn);
+
+#[cfg(windows)]
+impl LoadInMemoryAssemblyDelegate {
+  /// Load a C++/CLI assembly that was already loaded as a native module by the OS loader
+  ///
+  /// # Arguments
+  /// * `module` - `HMODULE` of the mixed mode assembly
+  /// * `assembly_path` - Path the module was loaded from
+  ///
+  /// # Safety
+  /// Assumes `module` is a valid `HMODULE` of a mixed mode assembly
+  pub unsafe fn invoke<A>(
+    &self,
+    module: *mut c_void,
+    assembly_path: A,
+  ) -> HostFxrResult<()>
+  where
+    A: IntoFxrBytes<char_t>,
+  {
+    HostFxrError::from_status(self.0(module, assembly_path.into_fxr_ptr()))
+  }
+}
+
+#[cfg(windows)]
+impl RuntimeDelegate for LoadInMemoryAssemblyDelegate {
+  type Fn = LoadInMemoryAssemblyFn;
+  const KIND: hostfxr_delegate_type =
+    dotnet_hostfxr_sys::hostfxr_delegate_type_hdt_load_in_memory_assembly;
+
+  fn from_native(native: Self::Fn) -> Self {
+    Self(native)
+  }
+}
+
+type LoadAssemblyFn = unsafe extern "C" fn(
+  assembly_path: *const char_t,
+  load_context: *mut c_void,
+  reserved: *mut c_void,
+) -> c_int;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoadAssemblyDelegate(LoadAssemblyFn);
+
+impl LoadAssemblyDelegate {
+  /// Load the assembly at `assembly_path` into the default `AssemblyLoadContext`, its
+  /// methods can then be resolved using [`GetFunctionPointerDelegate`]
+  ///
+  /// Requires .NET 8 or later.
+  pub fn invoke<A>(&self, assembly_path: A) -> HostFxrResult<()>
+  where
+    A: IntoFxrBytes<char_t>,
+  {
+    HostFxrError::from_status(unsafe {
+      self.0(assembly_path.into_fxr_ptr(), null_mut(), null_mut())
+    })
+  }
+}
+
+impl RuntimeDelegate for LoadAssemblyDelegate {
+  type Fn = LoadAssemblyFn;
+  const KIND: hostfxr_delegate_type = HOSTFXR_DELEGATE_TYPE_HDT_LOAD_ASSEMBLY;
+
+  fn from_native(native: Self::Fn) -> Self {
+    Self(native)
+  }
+}
+
+type LoadAssemblyBytesFn = unsafe extern "C" fn(
+  assembly_bytes: *const c_void,
+  assembly_bytes_len: usize,
+  symbols_bytes: *const c_void,
+  symbols_bytes_len: usize,
+  load_context: *mut c_void,
+  reserved: *mut c_void,
+) -> c_int;
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoadAssemblyBytesDelegate(LoadAssemblyBytesFn);
+
+impl LoadAssemblyBytesDelegate {
+  /// Load an assembly, and optionally its symbols, from memory into the default
+  /// `AssemblyLoadContext`, its methods can then be resolved using
+  /// [`GetFunctionPointerDelegate`]
+  ///
+  /// Requires .NET 8 or later.
+  pub fn invoke(&self, assembly: &[u8], symbols: Option<&[u8]>) -> HostFxrResult<()> {
+    let symbols = symbols.unwrap_or_default();
+    let symbols_ptr = match symbols.is_empty() {
+      true => null(),
+      false => symbols.as_ptr() as *const c_void,
+    };
+
+    HostFxrError::from_status(unsafe {
+      self.0(
+        assembly.as_ptr() as *const c_void,
+        assembly.len(),
+        symbols_ptr,
+        symbols.len(),
+        null_mut(),
+        null_mut(),
+      )
+    })
+  }
+}
+
+impl RuntimeDelegate for LoadAssemblyBytesDelegate {
+  type Fn = LoadAssemblyBytesFn;
+  const KIND: hostfxr_delegate_type = HOSTFXR_DELEGATE_TYPE_HDT_LOAD_ASSEMBLY_BYTES;
+
+  fn from_native(native: Self::Fn) -> Self {
+    Self(native)
+  }
+}
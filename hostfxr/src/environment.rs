@@ -0,0 +1,95 @@
+//! Installed .NET runtimes and SDKs as reported by `hostfxr_get_dotnet_environment_info`.
+use crate::string::IntoFxrString;
+use dotnet_hostfxr_sys::{
+  char_t, hostfxr_dotnet_environment_framework_info, hostfxr_dotnet_environment_info,
+  hostfxr_dotnet_environment_sdk_info,
+};
+use std::{path::PathBuf, slice::from_raw_parts};
+
+/// Information about the .NET installation used by hostfxr
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotnetEnvironmentInfo {
+  /// Version of the hostfxr library
+  pub hostfxr_version: String,
+  /// Commit hash the hostfxr library was built from
+  pub hostfxr_commit_hash: String,
+  /// Installed SDKs
+  pub sdks: Vec<DotnetSdkInfo>,
+  /// Installed frameworks, e.g. `Microsoft.NETCore.App`
+  pub frameworks: Vec<DotnetFrameworkInfo>,
+}
+
+/// An installed .NET SDK
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotnetSdkInfo {
+  pub version: String,
+  /// Directory of the SDK
+  pub path: PathBuf,
+}
+
+/// An installed shared framework
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotnetFrameworkInfo {
+  pub name: String,
+  pub version: String,
+  /// Directory containing the versions of the framework
+  pub path: PathBuf,
+}
+
+impl DotnetEnvironmentInfo {
+  /// Copy the environment info passed to the result callback
+  ///
+  /// # Safety
+  /// Assumes `info` and the items it points to are valid for the duration of the call.
+  pub(crate) unsafe fn from_native(info: &hostfxr_dotnet_environment_info) -> Self {
+    let sdks = slice(info.sdks, info.sdk_count as _)
+      .iter()
+      .map(|sdk| DotnetSdkInfo::from_native(sdk))
+      .collect();
+
+    let frameworks = slice(info.frameworks, info.framework_count as _)
+      .iter()
+      .map(|framework| DotnetFrameworkInfo::from_native(framework))
+      .collect();
+
+    Self {
+      hostfxr_version: string(info.hostfxr_version),
+      hostfxr_commit_hash: string(info.hostfxr_commit_hash),
+      sdks,
+      frameworks,
+    }
+  }
+}
+
+impl DotnetSdkInfo {
+  unsafe fn from_native(sdk: &hostfxr_dotnet_environment_sdk_info) -> Self {
+    Self {
+      version: string(sdk.version),
+      path: string(sdk.path).into(),
+    }
+  }
+}
+
+impl DotnetFrameworkInfo {
+  unsafe fn from_native(framework: &hostfxr_dotnet_environment_framework_info) -> Self {
+    Self {
+      name: string(framework.name),
+      version: string(framework.version),
+      path: string(framework.path).into(),
+    }
+  }
+}
+
+unsafe fn slice<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+  match ptr.is_null() {
+    true => &[],
+    false => from_raw_parts(ptr, len),
+  }
+}
+
+fn string(ptr: *const char_t) -> String {
+  match ptr.is_null() {
+    true => String::new(),
+    false => ptr.into_fxr_string(),
+  }
+}
@@ -1,7 +1,9 @@
+use crate::writer::take_last_error;
 use std::{
   error::Error,
   fmt::{Display, Formatter},
   num::TryFromIntError,
+  path::PathBuf,
 };
 
 pub type HostFxrResult<T> = Result<T, HostFxrError>;
@@ -15,50 +17,55 @@ pub enum HostFxrError {
   ImportNotFound,
   MissingHostPath,
   MissingDotnetRoot,
+  // Path passed to nethost is not valid unicode
+  InvalidPath(PathBuf),
   TryFromIntError(TryFromIntError),
 
   BadHandle,
   PoisonedHandle,
+  NullFunctionPointer,
+  AlreadyLoaded,
 
-  // Failure
-  InvalidArgFailure,
-  CoreHostLibLoadFailure,
-  CoreHostLibMissingFailure,
-  CoreHostEntryPointFailure,
-  CoreHostCurHostFindFailure,
+  // Hosting failures, holding the text reported to the error writer if one was installed
+  // using `HostFxrLibrary::set_error_writer`
+  InvalidArgFailure(Option<String>),
+  CoreHostLibLoadFailure(Option<String>),
+  CoreHostLibMissingFailure(Option<String>),
+  CoreHostEntryPointFailure(Option<String>),
+  CoreHostCurHostFindFailure(Option<String>),
   // unused
-  CoreClrResolveFailure,
-  CoreClrBindFailure,
-  CoreClrInitFailure,
-  CoreClrExeFailure,
-  ResolverInitFailure,
-  ResolverResolveFailure,
-  LibHostCurExeFindFailure,
-  LibHostInitFailure,
+  CoreClrResolveFailure(Option<String>),
+  CoreClrBindFailure(Option<String>),
+  CoreClrInitFailure(Option<String>),
+  CoreClrExeFailure(Option<String>),
+  ResolverInitFailure(Option<String>),
+  ResolverResolveFailure(Option<String>),
+  LibHostCurExeFindFailure(Option<String>),
+  LibHostInitFailure(Option<String>),
   // unused
-  LibHostExecModeFailure,
-  LibHostSdkFindFailure,
-  LibHostInvalidArgs,
-  InvalidConfigFile,
-  AppArgNotRunnable,
-  AppHostExeNotBoundFailure,
-  FrameworkMissingFailure,
-  HostApiFailed,
-  HostApiBufferTooSmall,
-  LibHostUnknownCommand,
-  LibHostAppRootFindFailure,
-  SdkResolverResolveFailure,
-  FrameworkCompatFailure,
-  FrameworkCompatRetry,
+  LibHostExecModeFailure(Option<String>),
+  LibHostSdkFindFailure(Option<String>),
+  LibHostInvalidArgs(Option<String>),
+  InvalidConfigFile(Option<String>),
+  AppArgNotRunnable(Option<String>),
+  AppHostExeNotBoundFailure(Option<String>),
+  FrameworkMissingFailure(Option<String>),
+  HostApiFailed(Option<String>),
+  HostApiBufferTooSmall(Option<String>),
+  LibHostUnknownCommand(Option<String>),
+  LibHostAppRootFindFailure(Option<String>),
+  SdkResolverResolveFailure(Option<String>),
+  FrameworkCompatFailure(Option<String>),
+  FrameworkCompatRetry(Option<String>),
   // unused
-  BundleExtractionFailure,
-  BundleExtractionIOError,
-  LibHostDuplicateProperty,
-  HostApiUnsupportedVersion,
-  HostInvalidState,
-  HostPropertyNotFound,
-  CoreHostIncompatibleConfig,
-  HostApiUnsupportedScenario,
+  BundleExtractionFailure(Option<String>),
+  BundleExtractionIOError(Option<String>),
+  LibHostDuplicateProperty(Option<String>),
+  HostApiUnsupportedVersion(Option<String>),
+  HostInvalidState(Option<String>),
+  HostPropertyNotFound(Option<String>),
+  CoreHostIncompatibleConfig(Option<String>),
+  HostApiUnsupportedScenario(Option<String>),
 
   COR_E_ABANDONEDMUTEX,
   COR_E_AMBIGUOUSIMPLEMENTATION,
@@ -168,44 +175,44 @@ impl HostFxrError {
       // source  : https://github.com/dotnet/runtime/blob/1d9e50cb4735df46d3de0cee5791e97295eaf588/src/installer/corehost/error_codes.h#L8
       // find    : (\w+)\s*=\s*((?:0x)?[\da-f]+),
       // replace : $2 => Err(HostFxrError::$1),
-      0x80008081 => Err(HostFxrError::InvalidArgFailure),
-      0x80008082 => Err(HostFxrError::CoreHostLibLoadFailure),
-      0x80008083 => Err(HostFxrError::CoreHostLibMissingFailure),
-      0x80008084 => Err(HostFxrError::CoreHostEntryPointFailure),
-      0x80008085 => Err(HostFxrError::CoreHostCurHostFindFailure),
+      0x80008081 => Err(HostFxrError::InvalidArgFailure(take_last_error())),
+      0x80008082 => Err(HostFxrError::CoreHostLibLoadFailure(take_last_error())),
+      0x80008083 => Err(HostFxrError::CoreHostLibMissingFailure(take_last_error())),
+      0x80008084 => Err(HostFxrError::CoreHostEntryPointFailure(take_last_error())),
+      0x80008085 => Err(HostFxrError::CoreHostCurHostFindFailure(take_last_error())),
       // 0x80008086 => Err(HostFxrError::unused),
-      0x80008087 => Err(HostFxrError::CoreClrResolveFailure),
-      0x80008088 => Err(HostFxrError::CoreClrBindFailure),
-      0x80008089 => Err(HostFxrError::CoreClrInitFailure),
-      0x8000808a => Err(HostFxrError::CoreClrExeFailure),
-      0x8000808b => Err(HostFxrError::ResolverInitFailure),
-      0x8000808c => Err(HostFxrError::ResolverResolveFailure),
-      0x8000808d => Err(HostFxrError::LibHostCurExeFindFailure),
-      0x8000808e => Err(HostFxrError::LibHostInitFailure),
+      0x80008087 => Err(HostFxrError::CoreClrResolveFailure(take_last_error())),
+      0x80008088 => Err(HostFxrError::CoreClrBindFailure(take_last_error())),
+      0x80008089 => Err(HostFxrError::CoreClrInitFailure(take_last_error())),
+      0x8000808a => Err(HostFxrError::CoreClrExeFailure(take_last_error())),
+      0x8000808b => Err(HostFxrError::ResolverInitFailure(take_last_error())),
+      0x8000808c => Err(HostFxrError::ResolverResolveFailure(take_last_error())),
+      0x8000808d => Err(HostFxrError::LibHostCurExeFindFailure(take_last_error())),
+      0x8000808e => Err(HostFxrError::LibHostInitFailure(take_last_error())),
       // 0x8000808f => Err(HostFxrError::unused),
-      0x80008090 => Err(HostFxrError::LibHostExecModeFailure),
-      0x80008091 => Err(HostFxrError::LibHostSdkFindFailure),
-      0x80008092 => Err(HostFxrError::LibHostInvalidArgs),
-      0x80008093 => Err(HostFxrError::InvalidConfigFile),
-      0x80008094 => Err(HostFxrError::AppArgNotRunnable),
-      0x80008095 => Err(HostFxrError::AppHostExeNotBoundFailure),
-      0x80008096 => Err(HostFxrError::FrameworkMissingFailure),
-      0x80008097 => Err(HostFxrError::HostApiFailed),
-      0x80008098 => Err(HostFxrError::HostApiBufferTooSmall),
-      0x80008099 => Err(HostFxrError::LibHostUnknownCommand),
-      0x8000809a => Err(HostFxrError::LibHostAppRootFindFailure),
-      0x8000809b => Err(HostFxrError::SdkResolverResolveFailure),
-      0x8000809c => Err(HostFxrError::FrameworkCompatFailure),
-      0x8000809d => Err(HostFxrError::FrameworkCompatRetry),
+      0x80008090 => Err(HostFxrError::LibHostExecModeFailure(take_last_error())),
+      0x80008091 => Err(HostFxrError::LibHostSdkFindFailure(take_last_error())),
+      0x80008092 => Err(HostFxrError::LibHostInvalidArgs(take_last_error())),
+      0x80008093 => Err(HostFxrError::InvalidConfigFile(take_last_error())),
+      0x80008094 => Err(HostFxrError::AppArgNotRunnable(take_last_error())),
+      0x80008095 => Err(HostFxrError::AppHostExeNotBoundFailure(take_last_error())),
+      0x80008096 => Err(HostFxrError::FrameworkMissingFailure(take_last_error())),
+      0x80008097 => Err(HostFxrError::HostApiFailed(take_last_error())),
+      0x80008098 => Err(HostFxrError::HostApiBufferTooSmall(take_last_error())),
+      0x80008099 => Err(HostFxrError::LibHostUnknownCommand(take_last_error())),
+      0x8000809a => Err(HostFxrError::LibHostAppRootFindFailure(take_last_error())),
+      0x8000809b => Err(HostFxrError::SdkResolverResolveFailure(take_last_error())),
+      0x8000809c => Err(HostFxrError::FrameworkCompatFailure(take_last_error())),
+      0x8000809d => Err(HostFxrError::FrameworkCompatRetry(take_last_error())),
       // 0x8000809e => Err(HostFxrError::unused),
-      0x8000809f => Err(HostFxrError::BundleExtractionFailure),
-      0x800080a0 => Err(HostFxrError::BundleExtractionIOError),
-      0x800080a1 => Err(HostFxrError::LibHostDuplicateProperty),
-      0x800080a2 => Err(HostFxrError::HostApiUnsupportedVersion),
-      0x800080a3 => Err(HostFxrError::HostInvalidState),
-      0x800080a4 => Err(HostFxrError::HostPropertyNotFound),
-      0x800080a5 => Err(HostFxrError::CoreHostIncompatibleConfig),
-      0x800080a6 => Err(HostFxrError::HostApiUnsupportedScenario),
+      0x8000809f => Err(HostFxrError::BundleExtractionFailure(take_last_error())),
+      0x800080a0 => Err(HostFxrError::BundleExtractionIOError(take_last_error())),
+      0x800080a1 => Err(HostFxrError::LibHostDuplicateProperty(take_last_error())),
+      0x800080a2 => Err(HostFxrError::HostApiUnsupportedVersion(take_last_error())),
+      0x800080a3 => Err(HostFxrError::HostInvalidState(take_last_error())),
+      0x800080a4 => Err(HostFxrError::HostPropertyNotFound(take_last_error())),
+      0x800080a5 => Err(HostFxrError::CoreHostIncompatibleConfig(take_last_error())),
+      0x800080a6 => Err(HostFxrError::HostApiUnsupportedScenario(take_last_error())),
 
       // source  : https://github.com/dotnet/runtime/blob/1d9e50cb4735df46d3de0cee5791e97295eaf588/src/libraries/Common/src/System/HResults.cs#L25
       // find    : internal const int (\w+) = unchecked\(\(int\)([^\)]+)\);
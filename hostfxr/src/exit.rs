@@ -0,0 +1,75 @@
+//! Exit codes of managed apps run through hostfxr.
+use crate::{HostFxrError, HostFxrResult};
+use std::fmt;
+
+/// Facility of the status codes hostfxr reports hosting failures with, see `error_codes.h`
+///
+/// The whole facility is matched rather than the codes known to
+/// [`HostFxrError::from_status`], so failures added by newer hosts are not mistaken for
+/// exit codes.
+const HOSTING_FAILURES: std::ops::RangeInclusive<u32> = 0x80008000..=0x800080ff;
+
+/// Exit code of a managed app
+///
+/// Returned when running an app through hostfxr, hosting failures are reported as
+/// [`HostFxrError`] instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExitCode(i32);
+
+impl ExitCode {
+  pub const SUCCESS: Self = Self(0);
+
+  /// Separate hosting failures from the exit code of the app in `status`
+  pub(crate) fn from_status(status: i32) -> HostFxrResult<Self> {
+    if HOSTING_FAILURES.contains(&(status as u32)) {
+      HostFxrError::from_status(status)?;
+    }
+
+    Ok(Self(status))
+  }
+
+  /// The exit code as returned by the app
+  pub fn code(self) -> i32 {
+    self.0
+  }
+
+  pub fn success(self) -> bool {
+    self == Self::SUCCESS
+  }
+}
+
+impl From<ExitCode> for i32 {
+  fn from(exit_code: ExitCode) -> Self {
+    exit_code.0
+  }
+}
+
+impl fmt::Display for ExitCode {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "exit code: {}", self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::ExitCode;
+  use crate::HostFxrError;
+
+  #[test]
+  fn test_from_status() {
+    assert_eq!(ExitCode::from_status(0).unwrap(), ExitCode::SUCCESS);
+    assert_eq!(ExitCode::from_status(3).unwrap().code(), 3);
+    assert_eq!(ExitCode::from_status(-1).unwrap().code(), -1);
+
+    match ExitCode::from_status(0x80008096u32 as i32) {
+      Err(HostFxrError::FrameworkMissingFailure(_)) => {}
+      other => panic!("Expected `FrameworkMissingFailure`, got {:?}", other),
+    }
+
+    // Not in the `error_codes.h` this was written against
+    assert!(matches!(
+      ExitCode::from_status(0x800080ffu32 as i32),
+      Err(HostFxrError::Unexpected(_))
+    ));
+  }
+}
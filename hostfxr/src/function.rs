@@ -0,0 +1,133 @@
+//! Function pointers returned by the runtime delegates.
+use crate::HostFxr;
+use std::{ffi::c_void, fmt, marker::PhantomData, ops::Deref, ptr::NonNull};
+
+mod private {
+  pub trait Sealed {}
+}
+
+/// A native function pointer type managed methods can be bound to.
+///
+/// Implemented for `extern "C" fn` and `unsafe extern "C" fn` of up to 12 arguments, it
+/// cannot be implemented outside of this crate.
+pub trait FunctionPointer: Copy + private::Sealed {
+  /// Whether calling the function pointer requires `unsafe`
+  const IS_UNSAFE: bool;
+
+  /// Convert a pointer returned by the runtime into the function pointer type.
+  ///
+  /// # Safety
+  /// Assumes `ptr` points to a function with a matching signature and calling convention.
+  unsafe fn from_ptr(ptr: NonNull<c_void>) -> Self;
+
+  /// Get the address of the function.
+  fn as_ptr(&self) -> *const c_void;
+}
+
+macro_rules! function_pointer_impl {
+  ($($arg:ident),*) => {
+    impl<Ret, $($arg),*> private::Sealed for extern "C" fn($($arg),*) -> Ret {}
+    impl<Ret, $($arg),*> FunctionPointer for extern "C" fn($($arg),*) -> Ret {
+      const IS_UNSAFE: bool = false;
+
+      unsafe fn from_ptr(ptr: NonNull<c_void>) -> Self {
+        std::mem::transmute(ptr.as_ptr())
+      }
+
+      fn as_ptr(&self) -> *const c_void {
+        *self as *const c_void
+      }
+    }
+
+    impl<Ret, $($arg),*> private::Sealed for unsafe extern "C" fn($($arg),*) -> Ret {}
+    impl<Ret, $($arg),*> FunctionPointer for unsafe extern "C" fn($($arg),*) -> Ret {
+      const IS_UNSAFE: bool = true;
+
+      unsafe fn from_ptr(ptr: NonNull<c_void>) -> Self {
+        std::mem::transmute(ptr.as_ptr())
+      }
+
+      fn as_ptr(&self) -> *const c_void {
+        *self as *const c_void
+      }
+    }
+  };
+}
+
+function_pointer_impl!();
+function_pointer_impl!(A1);
+function_pointer_impl!(A1, A2);
+function_pointer_impl!(A1, A2, A3);
+function_pointer_impl!(A1, A2, A3, A4);
+function_pointer_impl!(A1, A2, A3, A4, A5);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6, A7);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6, A7, A8);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6, A7, A8, A9);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11);
+function_pointer_impl!(A1, A2, A3, A4, A5, A6, A7, A8, A9, A10, A11, A12);
+
+/// A function pointer into managed code, only valid while the [`HostFxr`] context that
+/// produced it is alive.
+///
+/// Dereferences to `F` so it can be called directly.
+#[derive(Clone, Copy)]
+pub struct ManagedFunction<'ctx, F: FunctionPointer> {
+  function: F,
+  phantom: PhantomData<&'ctx HostFxr<'ctx>>,
+}
+
+impl<'ctx, F: FunctionPointer> ManagedFunction<'ctx, F> {
+  pub(crate) fn new(function: F) -> Self {
+    Self {
+      function,
+      phantom: PhantomData,
+    }
+  }
+
+  /// Get the underlying function pointer, detached from the lifetime of the context.
+  ///
+  /// # Safety
+  /// The function pointer must not be called after the context is dropped.
+  pub unsafe fn into_inner(self) -> F {
+    self.function
+  }
+}
+
+impl<F: FunctionPointer> Deref for ManagedFunction<'_, F> {
+  type Target = F;
+
+  fn deref(&self) -> &Self::Target {
+    &self.function
+  }
+}
+
+impl<F: FunctionPointer> fmt::Debug for ManagedFunction<'_, F> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_tuple("ManagedFunction")
+      .field(&self.function.as_ptr())
+      .finish()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{FunctionPointer, ManagedFunction};
+  use std::{ffi::c_void, ptr::NonNull};
+
+  extern "C" fn add(a: i32, b: i32) -> i32 {
+    a + b
+  }
+
+  #[test]
+  fn test_from_ptr() {
+    type AddFn = extern "C" fn(i32, i32) -> i32;
+
+    let ptr = NonNull::new(add as *mut c_void).unwrap();
+    let add = ManagedFunction::new(unsafe { AddFn::from_ptr(ptr) });
+
+    assert_eq!(add(1, 2), 3);
+    assert_eq!(add.as_ptr(), ptr.as_ptr() as *const c_void);
+  }
+}
@@ -1,5 +1,14 @@
-use delegate::{LoadAssemblyAndGetFunctionPointerDelegate, RuntimeDelegate};
+use context::{HostFxrContextKind, PropertyDiff};
+#[cfg(windows)]
+use delegate::LoadInMemoryAssemblyDelegate;
+use delegate::{
+  GetFunctionPointerDelegate, LoadAssemblyAndGetFunctionPointerDelegate,
+  LoadAssemblyBytesDelegate, LoadAssemblyDelegate, RuntimeDelegate,
+};
 use dotnet_hostfxr_sys::char_t;
+use exit::ExitCode;
+use function::{FunctionPointer, ManagedFunction};
+use once_cell::sync::OnceCell;
 use std::{collections::HashMap, ffi::c_void, ops::DerefMut, ptr::NonNull, sync::Mutex};
 use string::IntoFxrBytes;
 use symbol::{
@@ -7,20 +16,32 @@ use symbol::{
   GetRuntimePropertyValueSymbol, RunAppSymbol, SetRuntimePropertyValueSymbol,
 };
 
+pub mod context;
 pub mod delegate;
+pub mod environment;
 pub mod error;
+pub mod exit;
+pub mod function;
+pub mod sdk;
 pub mod symbol;
 pub use error::*;
 
 pub mod library;
 pub use library::*;
 
+mod locate;
 mod nethost;
 mod parameters;
 mod string;
+mod writer;
+pub use locate::HostFxrLocator;
+pub use parameters::{HostFxrParameters, HostFxrStartupInfo};
+pub use writer::ErrorWriterGuard;
 
 #[derive(Debug)]
 pub struct HostFxr<'lib> {
+  // Locked for every hostfxr export taking the context handle.  Runtime delegates do not
+  // take it and are safe to call concurrently once resolved, so they are not locked.
   handle: Mutex<NonNull<c_void>>,
 
   close: CloseSymbol<'lib>,
@@ -29,17 +50,24 @@ pub struct HostFxr<'lib> {
   set_runtime_property: SetRuntimePropertyValueSymbol<'lib>,
   get_runtime_property: GetRuntimePropertyValueSymbol<'lib>,
   get_runtime_properties: GetRuntimePropertiesSymbol<'lib>,
-  load_assembly_and_get_function_pointer: LoadAssemblyAndGetFunctionPointerDelegate,
+  kind: HostFxrContextKind,
+  // Getting a runtime delegate loads the runtime, deferred so properties can be set first
+  load_assembly_and_get_function_pointer:
+    OnceCell<LoadAssemblyAndGetFunctionPointerDelegate>,
+  get_function_pointer: OnceCell<GetFunctionPointerDelegate>,
+  load_assembly: OnceCell<LoadAssemblyDelegate>,
+  load_assembly_bytes: OnceCell<LoadAssemblyBytesDelegate>,
+  #[cfg(windows)]
+  load_in_memory_assembly: OnceCell<LoadInMemoryAssemblyDelegate>,
 }
 
 impl<'lib> HostFxr<'lib> {
+  /// Wrap an initialized host context, the runtime is loaded on first use of a runtime
+  /// delegate
   pub fn new(
-    mut handle: NonNull<c_void>,
+    handle: NonNull<c_void>,
     library: &HostFxrLibrary<'lib>,
   ) -> HostFxrResult<Self> {
-    let load_assembly_and_get_function_pointer =
-      library.get_runtime_delegate(&mut handle)?;
-
     Ok(Self {
       handle: Mutex::new(handle),
       close: library.close.clone(),
@@ -48,10 +76,65 @@ impl<'lib> HostFxr<'lib> {
       set_runtime_property: library.set_runtime_property.clone(),
       get_runtime_property: library.get_runtime_property.clone(),
       get_runtime_properties: library.get_runtime_properties.clone(),
-      load_assembly_and_get_function_pointer,
+      kind: HostFxrContextKind::Primary,
+      load_assembly_and_get_function_pointer: OnceCell::new(),
+      get_function_pointer: OnceCell::new(),
+      load_assembly: OnceCell::new(),
+      load_assembly_bytes: OnceCell::new(),
+      #[cfg(windows)]
+      load_in_memory_assembly: OnceCell::new(),
     })
   }
 
+  pub(crate) fn with_kind(mut self, kind: HostFxrContextKind) -> Self {
+    self.kind = kind;
+    self
+  }
+
+  /// Whether this context loaded the runtime or was validated against the running one
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::{context::HostFxrContextKind, HostFxrLibrary};
+  ///
+  /// let hostfxr = HostFxrLibrary::get().unwrap();
+  /// let ctx = hostfxr.initialize_runtime_config("plugin.runtimeconfig.json", None).unwrap();
+  ///
+  /// if let HostFxrContextKind::Secondary(_) = ctx.kind() {
+  ///   for diff in ctx.runtime_property_diffs().unwrap() {
+  ///     eprintln!("`{}` = `{}`, running with {:?}", diff.name, diff.requested, diff.active);
+  ///   }
+  /// }
+  /// ```
+  pub fn kind(&self) -> HostFxrContextKind {
+    self.kind
+  }
+
+  /// Get the runtime properties requested by this secondary context that differ from the
+  /// running runtime, always empty for a primary context
+  pub fn runtime_property_diffs(&self) -> HostFxrResult<Vec<PropertyDiff>> {
+    if self.kind.is_primary() {
+      return Ok(Vec::new());
+    }
+
+    let requested = self.get_runtime_properties()?;
+    let active = self.get_runtime_properties.invoke_active()?;
+
+    let mut diffs: Vec<_> = requested
+      .into_iter()
+      .filter(|(name, value)| active.get(name) != Some(value))
+      .map(|(name, requested)| PropertyDiff {
+        active: active.get(&name).cloned(),
+        name,
+        requested,
+      })
+      .collect();
+
+    diffs.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(diffs)
+  }
+
   /// Set the runtime property value name
   ///
   /// # Arguments
@@ -146,30 +229,33 @@ impl<'lib> HostFxr<'lib> {
     self.get_runtime_properties.invoke(handle.deref_mut())
   }
 
-  /// Load CoreCLR and run the application for an initialized host context
+  /// Load CoreCLR and run the application for an initialized host context, returning the
+  /// exit code of the application
   ///
   /// The host_context_handle must have been initialized using
-  /// hostfxr_initialize_for_dotnet_command_line.
+  /// hostfxr_initialize_for_dotnet_command_line.  Hosting failures are returned as errors,
+  /// any other value returned by `Main` as the [`ExitCode`].
   ///
   /// # Example
   /// ```
   ///
   /// use dotnet_hostfxr::HostFxrLibrary;
   ///
-  /// fn run_app<A: AsRef<str>>(exe_path: A) {
+  /// fn run_app<A: AsRef<str>>(app_path: A, args: &[&str]) -> i32 {
   ///   let hostfxr = HostFxrLibrary::get().expect("Failed to initialize hostfxr");
-  ///   let mut hostfxr = hostfxr
-  ///     .initialize_command_line(
-  ///       &[exe_path.as_ref()],
-  ///       None,
-  ///     )
+  ///   let argv = std::iter::once(app_path.as_ref()).chain(args.iter().copied());
+  ///   let hostfxr = hostfxr
+  ///     .initialize_command_line(argv, None)
   ///     .expect("Failed to initialize hostfxr");
   ///
-  ///   hostfxr.run_app().expect(&format!("Failed to run app `{}`", exe_path.as_ref()));
+  ///   hostfxr
+  ///     .run_app()
+  ///     .expect(&format!("Failed to run app `{}`", app_path.as_ref()))
+  ///     .code()
   /// }
   ///
   /// ```
-  pub fn run_app(&self) -> HostFxrResult<()> {
+  pub fn run_app(&self) -> HostFxrResult<ExitCode> {
     let mut handle = self
       .handle
       .lock()
@@ -198,11 +284,11 @@ impl<'lib> HostFxr<'lib> {
   ///
   /// # Example
   /// ```
-  /// use dotnet_hostfxr::HostFxr;
+  /// use dotnet_hostfxr::{function::ManagedFunction, HostFxr};
   ///
   /// type AddFn = extern "C" fn(a: i32, b: i32) -> i32;
   ///
-  /// fn get_add_fn(mut ctx: HostFxr) -> AddFn {
+  /// fn get_add_fn<'ctx>(ctx: &'ctx HostFxr) -> ManagedFunction<'ctx, AddFn> {
   ///   ctx.load_assembly_and_get_delegate(
   ///     std::fs::canonicalize("../bridge/bin/Debug/net5.0/bridge.dll")
   ///       .unwrap()
@@ -220,25 +306,174 @@ impl<'lib> HostFxr<'lib> {
     type_name: T,
     method_name: M,
     delegate_type_name: D,
-  ) -> HostFxrResult<F>
+  ) -> HostFxrResult<ManagedFunction<'_, F>>
   where
+    F: FunctionPointer,
     A: IntoFxrBytes<char_t>,
     T: IntoFxrBytes<char_t>,
     M: IntoFxrBytes<char_t>,
     D: IntoFxrBytes<char_t>,
   {
-    let guard = self.handle.lock().unwrap();
-    let delegate = self.load_assembly_and_get_function_pointer.invoke(
+    let load_assembly_and_get_function_pointer =
+      self.cached_delegate(&self.load_assembly_and_get_function_pointer)?;
+
+    let delegate = load_assembly_and_get_function_pointer.invoke(
       assembly_path,
       type_name,
       method_name,
       delegate_type_name,
     )?;
 
-    // Attempt to keep rustc from optimizing away our lock
-    std::mem::drop(guard);
+    Ok(ManagedFunction::new(delegate))
+  }
+
+  /// Load the assembly at `assembly_path` in isolation and get a function pointer to the
+  /// static method `method_name` marked `[UnmanagedCallersOnly]`
+  ///
+  /// Unlike [`HostFxr::load_assembly_and_get_delegate`] no delegate type is needed and
+  /// calls skip the delegate marshalling stub, `F` must match the method signature and
+  /// calling convention, typically `extern "C" fn`.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::{function::ManagedFunction, HostFxr};
+  ///
+  /// type AddFn = extern "C" fn(a: i32, b: i32) -> i32;
+  ///
+  /// fn get_add_fn<'ctx>(ctx: &'ctx HostFxr) -> ManagedFunction<'ctx, AddFn> {
+  ///   ctx.load_unmanaged_callers_only(
+  ///     "add.dll",
+  ///     "Methods, add, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null",
+  ///     "Add",
+  ///   ).expect("Failed to resolve Add method")
+  /// }
+  /// ```
+  pub fn load_unmanaged_callers_only<F, A, T, M>(
+    &self,
+    assembly_path: A,
+    type_name: T,
+    method_name: M,
+  ) -> HostFxrResult<ManagedFunction<'_, F>>
+  where
+    F: FunctionPointer,
+    A: IntoFxrBytes<char_t>,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+  {
+    let load_assembly_and_get_function_pointer =
+      self.cached_delegate(&self.load_assembly_and_get_function_pointer)?;
+
+    let delegate = load_assembly_and_get_function_pointer.invoke_unmanaged_callers_only(
+      assembly_path,
+      type_name,
+      method_name,
+    )?;
+
+    Ok(ManagedFunction::new(delegate))
+  }
+
+  /// Get a function pointer to a static method of a type already loaded into the default
+  /// `AssemblyLoadContext`, for example by [`HostFxr::load_assembly`]
+  ///
+  /// # Arguments
+  ///  * `type_name` - Assembly qualified type name to find
+  ///  * `method_name` - Name of the method on the `type_name` to find. The method must be
+  ///    `static` and must match the signature of `delegate_type_name`.
+  ///  * `delegate_type_name` - Assembly qualified delegate type name for the method
+  ///    signature.
+  pub fn get_function_pointer<F, T, M, D>(
+    &self,
+    type_name: T,
+    method_name: M,
+    delegate_type_name: D,
+  ) -> HostFxrResult<ManagedFunction<'_, F>>
+  where
+    F: FunctionPointer,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+    D: IntoFxrBytes<char_t>,
+  {
+    let delegate = self.cached_delegate(&self.get_function_pointer)?.invoke(
+      type_name,
+      method_name,
+      delegate_type_name,
+    )?;
+
+    Ok(ManagedFunction::new(delegate))
+  }
 
-    Ok(delegate)
+  /// Get a function pointer to a static method marked `[UnmanagedCallersOnly]` of a type
+  /// already loaded into the default `AssemblyLoadContext`
+  ///
+  /// `F` must match the method signature and calling convention, typically
+  /// `extern "C" fn`.
+  pub fn get_unmanaged_callers_only<F, T, M>(
+    &self,
+    type_name: T,
+    method_name: M,
+  ) -> HostFxrResult<ManagedFunction<'_, F>>
+  where
+    F: FunctionPointer,
+    T: IntoFxrBytes<char_t>,
+    M: IntoFxrBytes<char_t>,
+  {
+    let delegate = self
+      .cached_delegate(&self.get_function_pointer)?
+      .invoke_unmanaged_callers_only(type_name, method_name)?;
+
+    Ok(ManagedFunction::new(delegate))
+  }
+
+  /// Load the assembly at `assembly_path` into the default `AssemblyLoadContext`
+  ///
+  /// Requires .NET 8 or later.
+  pub fn load_assembly<A>(&self, assembly_path: A) -> HostFxrResult<()>
+  where
+    A: IntoFxrBytes<char_t>,
+  {
+    self
+      .cached_delegate(&self.load_assembly)?
+      .invoke(assembly_path)
+  }
+
+  /// Load an assembly, and optionally its symbols, from memory into the default
+  /// `AssemblyLoadContext`
+  ///
+  /// Requires .NET 8 or later.
+  pub fn load_assembly_bytes(
+    &self,
+    assembly: &[u8],
+    symbols: Option<&[u8]>,
+  ) -> HostFxrResult<()> {
+    self
+      .cached_delegate(&self.load_assembly_bytes)?
+      .invoke(assembly, symbols)
+  }
+
+  /// Load a C++/CLI assembly already loaded as a native module
+  ///
+  /// # Safety
+  /// Assumes `module` is a valid `HMODULE` of a mixed mode assembly loaded from
+  /// `assembly_path`
+  #[cfg(windows)]
+  pub unsafe fn load_in_memory_assembly<A>(
+    &self,
+    module: *mut c_void,
+    assembly_path: A,
+  ) -> HostFxrResult<()>
+  where
+    A: IntoFxrBytes<char_t>,
+  {
+    self
+      .cached_delegate(&self.load_in_memory_assembly)?
+      .invoke(module, assembly_path)
+  }
+
+  fn cached_delegate<'a, D>(&self, cell: &'a OnceCell<D>) -> HostFxrResult<&'a D>
+  where
+    D: RuntimeDelegate,
+  {
+    cell.get_or_try_init(|| self.get_runtime_delegate())
   }
 
   pub fn get_runtime_delegate<D>(&self) -> HostFxrResult<D>
@@ -1,20 +1,32 @@
 use crate::{
   delegate::RuntimeDelegate,
+  environment::DotnetEnvironmentInfo,
   error::HostFxrResult,
+  exit::ExitCode,
+  locate::HostFxrLocator,
   nethost::get_hostfxr_path,
-  parameters::HostFxrParameters,
+  parameters::{HostFxrParameters, HostFxrStartupInfo},
+  sdk::{ResolveSdkFlags, ResolvedSdk},
   string::IntoFxrBytes,
   symbol::{
-    CloseSymbol, GetRuntimeDelegateSymbol, GetRuntimePropertiesSymbol,
-    GetRuntimePropertyValueSymbol, InitializeCommandLineSymbol, InitializeConfigSymbol,
-    RunAppSymbol, SetRuntimePropertyValueSymbol,
+    CloseSymbol, GetAvailableSdksSymbol, GetDotnetEnvironmentInfoSymbol,
+    GetRuntimeDelegateSymbol, GetRuntimePropertiesSymbol, GetRuntimePropertyValueSymbol,
+    InitializeCommandLineSymbol, InitializeConfigSymbol, MainBundleStartupInfoSymbol,
+    MainStartupInfoSymbol, MainSymbol, ResolveSdkSymbol, RunAppSymbol,
+    SetErrorWriterSymbol, SetRuntimePropertyValueSymbol,
   },
+  writer::ErrorWriterGuard,
   HostFxr, HostFxrError,
 };
 use dotnet_hostfxr_sys::char_t;
 use libloading::Library;
 use once_cell::sync::OnceCell;
-use std::{ffi::c_void, ptr::NonNull};
+use std::{
+  ffi::c_void,
+  path::{Path, PathBuf},
+  ptr::NonNull,
+  sync::Arc,
+};
 
 static LIBRARY: OnceCell<Library> = OnceCell::new();
 static CURRENT: OnceCell<HostFxrLibrary<'static>> = OnceCell::new();
@@ -30,6 +42,13 @@ pub struct HostFxrLibrary<'lib> {
   pub set_runtime_property: SetRuntimePropertyValueSymbol<'lib>,
   pub get_runtime_property: GetRuntimePropertyValueSymbol<'lib>,
   pub get_runtime_properties: GetRuntimePropertiesSymbol<'lib>,
+  pub set_error_writer: SetErrorWriterSymbol<'lib>,
+  pub get_dotnet_environment_info: GetDotnetEnvironmentInfoSymbol<'lib>,
+  pub resolve_sdk: ResolveSdkSymbol<'lib>,
+  pub get_available_sdks: GetAvailableSdksSymbol<'lib>,
+  pub main: MainSymbol<'lib>,
+  pub main_startupinfo: MainStartupInfoSymbol<'lib>,
+  pub main_bundle_startupinfo: MainBundleStartupInfoSymbol<'lib>,
 }
 
 impl HostFxrLibrary<'static> {
@@ -47,6 +66,45 @@ impl HostFxrLibrary<'static> {
     })
   }
 
+  /// Open the hostfxr library at `path` independently of the one returned by
+  /// [`HostFxrLibrary::get`]
+  ///
+  /// The library is unloaded once the returned value and all its clones are dropped.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::HostFxrLibrary;
+  ///
+  /// for path in &["/usr/share/dotnet/host/fxr/5.0.1/libhostfxr.so", "/opt/dotnet/host/fxr/6.0.0/libhostfxr.so"] {
+  ///   let hostfxr = HostFxrLibrary::open(path).unwrap();
+  ///   let info = hostfxr.library().environment_info();
+  ///
+  ///   println!("{}: {:?}", path, info);
+  /// }
+  /// ```
+  pub fn open<P: AsRef<Path>>(path: P) -> HostFxrResult<OwnedHostFxrLibrary> {
+    OwnedHostFxrLibrary::new(Library::new(path.as_ref())?)
+  }
+
+  /// Locate the hostfxr library using an assembly path or `dotnet_root` instead of the
+  /// global registration
+  pub fn locate() -> HostFxrLocator {
+    HostFxrLocator::new()
+  }
+
+  pub(crate) fn install(path: PathBuf) -> HostFxrResult<&'static Self> {
+    if LIBRARY.get().is_some() {
+      return Err(HostFxrError::AlreadyLoaded);
+    }
+
+    let library = Library::new(path)?;
+    if LIBRARY.try_insert(library).is_err() {
+      return Err(HostFxrError::AlreadyLoaded);
+    }
+
+    Self::get()
+  }
+
   fn get_library() -> HostFxrResult<&'static Library> {
     if let Some(library) = LIBRARY.get() {
       return Ok(library);
@@ -73,9 +131,171 @@ impl<'lib> HostFxrLibrary<'lib> {
       set_runtime_property: SetRuntimePropertyValueSymbol::new(library)?,
       get_runtime_property: GetRuntimePropertyValueSymbol::new(library)?,
       get_runtime_properties: GetRuntimePropertiesSymbol::new(library)?,
+      set_error_writer: SetErrorWriterSymbol::new(library)?,
+      get_dotnet_environment_info: GetDotnetEnvironmentInfoSymbol::new(library)?,
+      resolve_sdk: ResolveSdkSymbol::new(library)?,
+      get_available_sdks: GetAvailableSdksSymbol::new(library)?,
+      main: MainSymbol::new(library)?,
+      main_startupinfo: MainStartupInfoSymbol::new(library)?,
+      main_bundle_startupinfo: MainBundleStartupInfoSymbol::new(library)?,
     })
   }
 
+  /// Route hosting diagnostics written on the calling thread to `writer` instead of stderr
+  ///
+  /// Text written before a hosting failure is also attached to the returned
+  /// [`HostFxrError`], e.g. `FrameworkMissingFailure(Some(..))`.  The previous writer is
+  /// restored when the returned guard is dropped.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::HostFxrLibrary;
+  ///
+  /// let hostfxr = HostFxrLibrary::get().unwrap();
+  /// let _guard = hostfxr.set_error_writer(|message| eprintln!("hostfxr: {}", message));
+  ///
+  /// if let Err(err) = hostfxr.initialize_runtime_config("app.runtimeconfig.json", None) {
+  ///   eprintln!("Failed to initialize runtime: {}", err);
+  /// }
+  /// ```
+  pub fn set_error_writer<F>(&self, writer: F) -> ErrorWriterGuard<'lib>
+  where
+    F: FnMut(&str) + 'static,
+  {
+    ErrorWriterGuard::install(&self.set_error_writer, writer)
+  }
+
+  /// Route hosting diagnostics written on the calling thread to [`log::error!`]
+  #[cfg(feature = "log")]
+  pub fn set_log_error_writer(&self) -> ErrorWriterGuard<'lib> {
+    self.set_error_writer(|message| log::error!(target: "hostfxr", "{}", message))
+  }
+
+  /// Get the installed .NET SDKs and frameworks
+  ///
+  /// Requires hostfxr from .NET 6 or later, fails with [`HostFxrError::ImportNotFound`]
+  /// otherwise.
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::HostFxrLibrary;
+  ///
+  /// let info = HostFxrLibrary::get().unwrap().environment_info().unwrap();
+  /// for framework in info.frameworks {
+  ///   println!("{} {}", framework.name, framework.version);
+  /// }
+  /// ```
+  pub fn environment_info(&self) -> HostFxrResult<DotnetEnvironmentInfo> {
+    self.get_dotnet_environment_info.invoke::<&str>(None)
+  }
+
+  /// Get the .NET SDKs and frameworks installed in `dotnet_root`
+  pub fn environment_info_for<R>(
+    &self,
+    dotnet_root: R,
+  ) -> HostFxrResult<DotnetEnvironmentInfo>
+  where
+    R: IntoFxrBytes<char_t>,
+  {
+    self.get_dotnet_environment_info.invoke(Some(dotnet_root))
+  }
+
+  /// Resolve the SDK the `dotnet` executable in `exe_dir` would use when run from
+  /// `working_dir`, honoring `global.json`
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::{sdk::ResolveSdkFlags, HostFxrLibrary};
+  ///
+  /// let hostfxr = HostFxrLibrary::get().unwrap();
+  /// let sdk = hostfxr
+  ///   .resolve_sdk("/usr/share/dotnet", ".", ResolveSdkFlags::DISALLOW_PRERELEASE)
+  ///   .unwrap();
+  ///
+  /// println!("{:?} picked by {:?}", sdk.sdk_dir, sdk.global_json_path);
+  /// ```
+  pub fn resolve_sdk<E, W>(
+    &self,
+    exe_dir: E,
+    working_dir: W,
+    flags: ResolveSdkFlags,
+  ) -> HostFxrResult<ResolvedSdk>
+  where
+    E: IntoFxrBytes<char_t>,
+    W: IntoFxrBytes<char_t>,
+  {
+    self.resolve_sdk.invoke(exe_dir, working_dir, flags)
+  }
+
+  /// Get the directories of the SDKs installed next to the `dotnet` executable in
+  /// `exe_dir`, sorted by ascending version
+  pub fn available_sdks<E>(&self, exe_dir: E) -> HostFxrResult<Vec<PathBuf>>
+  where
+    E: IntoFxrBytes<char_t>,
+  {
+    self.get_available_sdks.invoke(exe_dir)
+  }
+
+  /// Run a managed app as a native app host would, returning the exit code of the app
+  ///
+  /// Without `startup_info` the command line is handled like the `dotnet` muxer does.
+  ///
+  /// # Arguments
+  /// * `args` - Command line, starting with the path of the host executable
+  /// * `startup_info` - Paths of the host, .NET installation and app
+  ///
+  /// # Example
+  /// ```no_run
+  /// use dotnet_hostfxr::{HostFxrLibrary, HostFxrStartupInfo};
+  ///
+  /// let host_path = std::env::current_exe().unwrap();
+  /// let host_path = host_path.to_string_lossy();
+  /// let hostfxr = HostFxrLibrary::get().unwrap();
+  /// let exit_code = hostfxr
+  ///   .run_app_host(
+  ///     std::env::args(),
+  ///     Some(HostFxrStartupInfo {
+  ///       host_path: host_path.clone(),
+  ///       dotnet_root: "/usr/share/dotnet".into(),
+  ///       app_path: "app.dll".into(),
+  ///       bundle_header_offset: None,
+  ///     }),
+  ///   )
+  ///   .unwrap();
+  ///
+  /// std::process::exit(exit_code.code());
+  /// ```
+  pub fn run_app_host<A, I>(
+    &self,
+    args: A,
+    startup_info: Option<HostFxrStartupInfo>,
+  ) -> HostFxrResult<ExitCode>
+  where
+    A: IntoIterator<Item = I>,
+    I: IntoFxrBytes<char_t>,
+  {
+    let startup_info = match startup_info {
+      Some(startup_info) => startup_info,
+      None => return self.main.invoke(args),
+    };
+
+    match startup_info.bundle_header_offset {
+      Some(bundle_header_offset) => self.main_bundle_startupinfo.invoke(
+        args,
+        startup_info.host_path,
+        startup_info.dotnet_root,
+        startup_info.app_path,
+        bundle_header_offset,
+      ),
+      None => self.main_startupinfo.invoke(
+        args,
+        startup_info.host_path,
+        startup_info.dotnet_root,
+        startup_info.app_path,
+      ),
+    }
+  }
+
   /// Initializes the hosting components for a dotnet command line running an application
   ///
   /// This function parses the specified command-line arguments to determine the application to run. It will
@@ -87,7 +307,8 @@ impl<'lib> HostFxrLibrary<'lib> {
   /// This function does not load the runtime.
   ///
   /// # Arguments
-  /// * `args` - Command-line arguments for running an application
+  /// * `args` - Command-line arguments for running an application, the app path followed
+  ///   by the arguments passed to `Main`
   /// * `parameters` - Additional parameters for initialization
   pub fn initialize_command_line<A, I>(
     &self,
@@ -114,7 +335,8 @@ impl<'lib> HostFxrLibrary<'lib> {
   /// This function does not load the runtime.
   ///
   /// If called when the runtime has already been loaded, this function will check if the specified runtime
-  /// config is compatible with the existing runtime.
+  /// config is compatible with the existing runtime.  The returned context is then a
+  /// secondary context, see [`HostFxr::kind`].
   ///
   /// # Arguments
   /// * `runtime_config` - Path to the .runtimeconfig.json file
@@ -129,7 +351,7 @@ impl<'lib> HostFxrLibrary<'lib> {
   {
     let handle = self.initialize_config.invoke(runtime_config, parameters)?;
     match handle {
-      Some(handle) => HostFxr::new(handle, self),
+      Some((handle, kind)) => Ok(HostFxr::new(handle, self)?.with_kind(kind)),
       None => Err(HostFxrError::BadHandle),
     }
   }
@@ -143,3 +365,75 @@ impl<'lib> HostFxrLibrary<'lib> {
     ))
   }
 }
+
+/// A [`HostFxrLibrary`] owning the loaded hostfxr library, returned by
+/// [`HostFxrLibrary::open`]
+#[derive(Debug, Clone)]
+pub struct OwnedHostFxrLibrary {
+  // Borrows from `_library`, declared first so it is dropped first
+  symbols: HostFxrLibrary<'static>,
+  _library: Arc<Library>,
+}
+
+impl OwnedHostFxrLibrary {
+  fn new(library: Library) -> HostFxrResult<Self> {
+    let library = Arc::new(library);
+
+    // The library lives on the heap until the last clone is dropped, the symbols never
+    // outlive it as they are only handed out borrowed from `self`
+    let borrowed = unsafe { &*Arc::as_ptr(&library) };
+    let symbols = HostFxrLibrary::from_library(borrowed)?;
+
+    Ok(Self {
+      symbols,
+      _library: library,
+    })
+  }
+
+  /// Get the exports of the owned library
+  pub fn library(&self) -> &HostFxrLibrary<'_> {
+    &self.symbols
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::HostFxrLibrary;
+  use crate::nethost::get_hostfxr_path;
+  use std::path::Path;
+
+  #[test]
+  fn test_open_twice() {
+    let path = get_hostfxr_path().unwrap();
+
+    // hostfxr is installed to `<dotnet_root>/host/fxr/<version>`
+    let dotnet_root = Path::new(&path).ancestors().nth(4).unwrap();
+    let dotnet_root = dotnet_root.to_string_lossy();
+    let dotnet_root = dotnet_root.as_ref();
+
+    for &first_dropped_first in &[true, false] {
+      let first = HostFxrLibrary::open(&path).unwrap();
+      let second = HostFxrLibrary::open(&path).unwrap();
+      let clone = first.clone();
+
+      let sdks = first.library().available_sdks(dotnet_root).unwrap();
+      assert_eq!(second.library().available_sdks(dotnet_root).unwrap(), sdks);
+
+      let remaining = if first_dropped_first {
+        drop(first);
+        second
+      } else {
+        drop(second);
+        first
+      };
+
+      // The library stays loaded as long as any owner is alive
+      assert_eq!(
+        remaining.library().available_sdks(dotnet_root).unwrap(),
+        sdks
+      );
+      drop(remaining);
+      assert_eq!(clone.library().available_sdks(dotnet_root).unwrap(), sdks);
+    }
+  }
+}
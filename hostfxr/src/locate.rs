@@ -0,0 +1,120 @@
+use crate::{
+  nethost::get_hostfxr_path_with, HostFxrError, HostFxrLibrary, HostFxrResult,
+  OwnedHostFxrLibrary,
+};
+use std::path::{Path, PathBuf};
+
+/// Locates the hostfxr library to load, returned by [`HostFxrLibrary::locate`]
+///
+/// # Example
+/// ```no_run
+/// use dotnet_hostfxr::HostFxrLibrary;
+///
+/// let hostfxr = HostFxrLibrary::locate()
+///   .assembly_path("app/app.dll")
+///   .install()
+///   .expect("Failed to load hostfxr");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HostFxrLocator {
+  assembly_path: Option<PathBuf>,
+  dotnet_root: Option<PathBuf>,
+  hostfxr_path: Option<PathBuf>,
+}
+
+impl HostFxrLocator {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Path to the app or component assembly, a self-contained or app-local hostfxr next to
+  /// it is preferred
+  pub fn assembly_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+    self.assembly_path = Some(path.as_ref().to_path_buf());
+    self
+  }
+
+  /// Root of the .NET installation to use, global registration and environment variables
+  /// are ignored
+  pub fn dotnet_root<P: AsRef<Path>>(mut self, path: P) -> Self {
+    self.dotnet_root = Some(path.as_ref().to_path_buf());
+    self
+  }
+
+  /// Load the hostfxr library at `path`, bypassing nethost entirely
+  pub fn hostfxr_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+    self.hostfxr_path = Some(path.into());
+    self
+  }
+
+  /// Get the path of the hostfxr library without loading it
+  ///
+  /// Fails with [`HostFxrError::InvalidPath`] if the assembly path or .NET root is not
+  /// valid unicode, rather than asking nethost about a different path.
+  pub fn find(&self) -> HostFxrResult<PathBuf> {
+    if let Some(path) = &self.hostfxr_path {
+      return Ok(path.clone());
+    }
+
+    let assembly_path = self.assembly_path.as_deref().map(to_str).transpose()?;
+    let dotnet_root = self.dotnet_root.as_deref().map(to_str).transpose()?;
+    let path = get_hostfxr_path_with(assembly_path, dotnet_root)?;
+
+    Ok(path.into())
+  }
+
+  /// Load the located hostfxr library and install it as the one returned by
+  /// [`HostFxrLibrary::get`]
+  ///
+  /// Fails with [`HostFxrError::AlreadyLoaded`](crate::HostFxrError::AlreadyLoaded) if a
+  /// hostfxr library was already loaded, including implicitly by [`HostFxrLibrary::get`].
+  pub fn install(self) -> HostFxrResult<&'static HostFxrLibrary<'static>> {
+    let path = self.find()?;
+
+    HostFxrLibrary::install(path)
+  }
+
+  /// Open the located hostfxr library independently of the one returned by
+  /// [`HostFxrLibrary::get`]
+  pub fn open(self) -> HostFxrResult<OwnedHostFxrLibrary> {
+    HostFxrLibrary::open(self.find()?)
+  }
+}
+
+fn to_str(path: &Path) -> HostFxrResult<&str> {
+  path
+    .to_str()
+    .ok_or_else(|| HostFxrError::InvalidPath(path.to_path_buf()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::HostFxrLocator;
+  use crate::HostFxrError;
+  use std::path::PathBuf;
+
+  #[test]
+  fn test_hostfxr_path() {
+    // nethost would resolve an installed hostfxr or fail, the explicit path is used as is
+    let explicit = PathBuf::from("/nonexistent/libhostfxr.so");
+    let path = HostFxrLocator::new()
+      .assembly_path("app/app.dll")
+      .dotnet_root("/nonexistent")
+      .hostfxr_path(&explicit)
+      .find()
+      .unwrap();
+
+    assert_eq!(path, explicit);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_invalid_path() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let invalid = PathBuf::from(OsStr::from_bytes(b"/app/\xff.dll"));
+    let result = HostFxrLocator::new().assembly_path(&invalid).find();
+
+    assert!(matches!(result, Err(HostFxrError::InvalidPath(path)) if path == invalid));
+  }
+}
@@ -1,5 +1,9 @@
-use crate::{error::HostFxrError, string::IntoFxrString};
-use std::ptr::null_mut;
+use crate::{
+  error::HostFxrError,
+  string::{IntoFxrBytes, IntoFxrString},
+};
+use dotnet_hostfxr_sys::{char_t, get_hostfxr_parameters, size_t};
+use std::{mem::size_of, ptr::null};
 
 /// MAX_PATH defines buffer size supplied to nethost.  This should be more than enough
 /// given that hostfxr will most likely be installed by an installer provided by Microsoft
@@ -8,29 +12,105 @@ use std::ptr::null_mut;
 /// Good places to test are in environments with NIX or xbps package managers.
 const MAX_PATH: usize = 4096;
 
+/// `HostApiBufferTooSmall`, the required buffer size is written back to `buffer_size`
+const HOST_API_BUFFER_TOO_SMALL: i32 = 0x80008098u32 as i32;
+
 /// Get `hostfxr` dynamic library path on system using global registration or environment
 /// variables.
 pub fn get_hostfxr_path() -> Result<String, HostFxrError> {
-  let mut buf = vec![0; MAX_PATH];
-  let mut buf_len = buf.len() as u64;
-  let status = unsafe {
-    dotnet_hostfxr_sys::get_hostfxr_path(
-      buf.as_mut_ptr(),
-      &mut buf_len as *mut _,
-      null_mut(),
-    )
+  get_hostfxr_path_with(None, None)
+}
+
+/// Get `hostfxr` dynamic library path using `get_hostfxr_parameters`
+///
+/// # Arguments
+/// * `assembly_path` - Path to the app or component assembly, a self-contained or app-local
+///   hostfxr next to it is preferred
+/// * `dotnet_root` - Root of the .NET installation to use, global registration and
+///   environment variables are ignored
+pub fn get_hostfxr_path_with(
+  assembly_path: Option<&str>,
+  dotnet_root: Option<&str>,
+) -> Result<String, HostFxrError> {
+  get_hostfxr_path_using(
+    assembly_path,
+    dotnet_root,
+    |buf, buf_len, parameters| unsafe {
+      dotnet_hostfxr_sys::get_hostfxr_path(buf, buf_len, parameters)
+    },
+  )
+}
+
+/// Call `get_hostfxr_path`, which is replaced with a fake in tests
+fn get_hostfxr_path_using<F>(
+  assembly_path: Option<&str>,
+  dotnet_root: Option<&str>,
+  mut get_hostfxr_path: F,
+) -> Result<String, HostFxrError>
+where
+  F: FnMut(*mut char_t, *mut size_t, *const get_hostfxr_parameters) -> i32,
+{
+  let assembly_path = assembly_path.map(IntoFxrBytes::<char_t>::into_fxr_bytes);
+  let dotnet_root = dotnet_root.map(IntoFxrBytes::<char_t>::into_fxr_bytes);
+  let parameters = get_hostfxr_parameters {
+    size: size_of::<get_hostfxr_parameters>() as _,
+    assembly_path: assembly_path.as_ref().map_or(null(), |path| path.as_ptr()),
+    dotnet_root: dotnet_root.as_ref().map_or(null(), |root| root.as_ptr()),
+  };
+
+  let parameters = match (&assembly_path, &dotnet_root) {
+    (None, None) => null(),
+    _ => &parameters as *const _,
   };
 
-  HostFxrError::from_status(status)?;
+  let mut buf = vec![0; MAX_PATH];
+  loop {
+    let mut buf_len = buf.len() as size_t;
+    let status = get_hostfxr_path(buf.as_mut_ptr(), &mut buf_len, parameters);
+
+    // Retry with the required size, paths can exceed `MAX_PATH`
+    if status == HOST_API_BUFFER_TOO_SMALL && buf_len as usize > buf.len() {
+      buf.resize(buf_len as usize, 0);
+      continue;
+    }
+
+    HostFxrError::from_status(status)?;
 
-  Ok(buf[..buf_len as usize - 1].into_fxr_string())
+    return Ok(buf[..buf_len as usize - 1].into_fxr_string());
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use std::path::Path;
+  use std::{path::Path, ptr::copy_nonoverlapping};
+
+  use super::{
+    get_hostfxr_path, get_hostfxr_path_using, HOST_API_BUFFER_TOO_SMALL, MAX_PATH,
+  };
+  use crate::string::{IntoFxrBytes, IntoFxrString};
+  use dotnet_hostfxr_sys::{char_t, get_hostfxr_parameters, size_t};
 
-  use super::get_hostfxr_path;
+  /// Write `path` to `buf` like nethost, failing if it does not fit
+  unsafe fn write_path(buf: *mut char_t, buf_len: *mut size_t, path: &str) -> i32 {
+    let path: Vec<char_t> = path.into_fxr_bytes();
+    if (*buf_len as usize) < path.len() {
+      *buf_len = path.len() as _;
+      return HOST_API_BUFFER_TOO_SMALL;
+    }
+
+    copy_nonoverlapping(path.as_ptr(), buf, path.len());
+    *buf_len = path.len() as _;
+    0
+  }
+
+  /// Read an optional nul terminated parameter
+  unsafe fn read_parameter(ptr: *const char_t) -> Option<String> {
+    if ptr.is_null() {
+      None
+    } else {
+      Some(ptr.into_fxr_string())
+    }
+  }
 
   #[test]
   fn test_get_hostfxr() {
@@ -47,4 +127,55 @@ mod tests {
       _ => assert_eq!(hostfxr, "libhostfxr.so"),
     };
   }
+
+  #[test]
+  fn test_buffer_too_small() {
+    let long = format!("/{}/libhostfxr.so", "a".repeat(MAX_PATH));
+    let mut calls = 0;
+
+    let path = get_hostfxr_path_using(None, None, |buf, buf_len, _| {
+      calls += 1;
+      unsafe { write_path(buf, buf_len, &long) }
+    })
+    .unwrap();
+
+    assert_eq!(path, long);
+    assert_eq!(calls, 2);
+  }
+
+  #[test]
+  fn test_parameters() {
+    let resolve = |assembly_path, dotnet_root| {
+      get_hostfxr_path_using(assembly_path, dotnet_root, |buf, buf_len, parameters| {
+        let resolved = if parameters.is_null() {
+          "global".to_string()
+        } else {
+          let parameters: &get_hostfxr_parameters = unsafe { &*parameters };
+          assert_eq!(
+            parameters.size as usize,
+            std::mem::size_of::<get_hostfxr_parameters>()
+          );
+
+          format!(
+            "{:?} {:?}",
+            unsafe { read_parameter(parameters.assembly_path) },
+            unsafe { read_parameter(parameters.dotnet_root) },
+          )
+        };
+
+        unsafe { write_path(buf, buf_len, &resolved) }
+      })
+      .unwrap()
+    };
+
+    assert_eq!(resolve(None, None), "global");
+    assert_eq!(
+      resolve(Some("app/app.dll"), None),
+      r#"Some("app/app.dll") None"#
+    );
+    assert_eq!(
+      resolve(None, Some("/usr/share/dotnet")),
+      r#"None Some("/usr/share/dotnet")"#
+    );
+  }
 }
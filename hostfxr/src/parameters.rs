@@ -1,32 +1,66 @@
-use crate::string::IntoFxrPtr;
-use dotnet_hostfxr_sys::hostfxr_initialize_parameters;
-use std::{
-  borrow::Cow,
-  mem::{size_of, MaybeUninit},
-};
+use crate::string::IntoFxrBytes;
+use dotnet_hostfxr_sys::{char_t, hostfxr_initialize_parameters};
+use std::{borrow::Cow, mem::size_of, ptr::null};
 
+/// Additional parameters for initializing the hosting components
 #[derive(Debug, Clone)]
 pub struct HostFxrParameters<'a> {
   pub host_path: Cow<'a, str>,
   pub dotnet_root: Cow<'a, str>,
 }
 
-impl<'a> IntoFxrPtr<hostfxr_initialize_parameters> for Option<HostFxrParameters<'a>> {
-  unsafe fn into_fxr_ptr(self) -> *const hostfxr_initialize_parameters {
-    let parameters = match self {
-      Some(parameters) => MaybeUninit::new(hostfxr_initialize_parameters {
-        size: size_of::<hostfxr_initialize_parameters>() as u64,
-        host_path: parameters.host_path.into_fxr_ptr(),
-        dotnet_root: parameters.dotnet_root.into_fxr_ptr(),
-      }),
-      None => MaybeUninit::zeroed(),
-    };
+impl<'a> HostFxrParameters<'a> {
+  pub(crate) fn into_raw(self) -> RawParameters {
+    RawParameters::new(self)
+  }
+}
 
-    let ptr = parameters.as_ptr();
+/// Native parameters owning the strings they point to
+///
+/// hostfxr only reads the parameters during the call, keep this alive until it returns.
+pub(crate) struct RawParameters {
+  raw: hostfxr_initialize_parameters,
+  _host_path: Vec<char_t>,
+  _dotnet_root: Vec<char_t>,
+}
 
-    #[allow(clippy::forget_copy)]
-    std::mem::forget(parameters);
+impl RawParameters {
+  fn new(parameters: HostFxrParameters) -> Self {
+    let host_path = parameters.host_path.into_fxr_bytes();
+    let dotnet_root = parameters.dotnet_root.into_fxr_bytes();
+
+    Self {
+      raw: hostfxr_initialize_parameters {
+        size: size_of::<hostfxr_initialize_parameters>() as _,
+        host_path: host_path.as_ptr(),
+        dotnet_root: dotnet_root.as_ptr(),
+      },
+      _host_path: host_path,
+      _dotnet_root: dotnet_root,
+    }
+  }
 
-    ptr
+  /// Pointer passed to hostfxr, null if there are no parameters
+  pub(crate) fn as_ptr(
+    parameters: &Option<Self>,
+  ) -> *const hostfxr_initialize_parameters {
+    parameters
+      .as_ref()
+      .map_or(null(), |parameters| &parameters.raw)
   }
 }
+
+/// Startup information of a native app host, see [`HostFxrLibrary::run_app_host`]
+///
+/// [`HostFxrLibrary::run_app_host`]: crate::HostFxrLibrary::run_app_host
+#[derive(Debug, Clone)]
+pub struct HostFxrStartupInfo<'a> {
+  /// Path of the host executable
+  pub host_path: Cow<'a, str>,
+  /// Root of the .NET installation to run the app with
+  pub dotnet_root: Cow<'a, str>,
+  /// Path of the managed app assembly
+  pub app_path: Cow<'a, str>,
+  /// Offset of the bundle header if the host is a single-file bundle
+  pub bundle_header_offset: Option<i64>,
+}
@@ -0,0 +1,38 @@
+//! SDK resolution as performed by the `dotnet` muxer.
+use dotnet_hostfxr_sys::{
+  hostfxr_resolve_sdk2_flags_t, hostfxr_resolve_sdk2_flags_t_disallow_prerelease,
+};
+use std::{ops::BitOr, path::PathBuf};
+
+/// Flags of `hostfxr_resolve_sdk2`
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResolveSdkFlags(hostfxr_resolve_sdk2_flags_t);
+
+impl ResolveSdkFlags {
+  pub const NONE: Self = Self(0);
+  /// Ignore prerelease SDKs unless `global.json` requests one
+  pub const DISALLOW_PRERELEASE: Self =
+    Self(hostfxr_resolve_sdk2_flags_t_disallow_prerelease);
+
+  pub fn bits(self) -> hostfxr_resolve_sdk2_flags_t {
+    self.0
+  }
+}
+
+impl BitOr for ResolveSdkFlags {
+  type Output = Self;
+
+  fn bitor(self, rhs: Self) -> Self::Output {
+    Self(self.0 | rhs.0)
+  }
+}
+
+/// Result of `hostfxr_resolve_sdk2`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedSdk {
+  /// Directory of the resolved SDK, `None` if no installed SDK matches
+  pub sdk_dir: Option<PathBuf>,
+  /// Path of the `global.json` used to pick the SDK, if any
+  pub global_json_path: Option<PathBuf>,
+}
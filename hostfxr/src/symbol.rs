@@ -1,15 +1,22 @@
 use crate::{
+  context::HostFxrContextKind,
   delegate::RuntimeDelegate,
-  parameters::HostFxrParameters,
+  environment::DotnetEnvironmentInfo,
+  exit::ExitCode,
+  parameters::{HostFxrParameters, RawParameters},
+  sdk::{ResolveSdkFlags, ResolvedSdk},
   string::{IntoFxrBytes, IntoFxrPtr, IntoFxrString},
+  writer::clear_last_error,
   HostFxrError, HostFxrResult,
 };
 use dotnet_hostfxr_sys::char_t;
 use libloading::{Library, Symbol};
 use std::{
+  cell::RefCell,
   collections::HashMap,
   ffi::c_void,
-  mem::{ManuallyDrop, MaybeUninit},
+  mem::MaybeUninit,
+  path::PathBuf,
   ptr::{null, null_mut, NonNull},
 };
 
@@ -37,7 +44,8 @@ impl<'lib> InitializeCommandLineSymbol<'lib> {
   /// This function does not load the runtime.
   ///
   /// # Arguments
-  /// * `args` - Command-line arguments for running an application
+  /// * `args` - Command-line arguments for running an application, the app path followed
+  ///   by the arguments passed to `Main`
   /// * `parameters` - Additional parameters for initialization
   pub fn invoke<A, I>(
     &self,
@@ -54,22 +62,20 @@ impl<'lib> InitializeCommandLineSymbol<'lib> {
       .lift_option()
       .expect("Symbol `hostfxr_initialize_for_dotnet_command_line` not found");
 
-    // Copy each argument into a HostFxr API compatible string, leak created string, collect
-    // into a vector that is leaked as well.
-    let (argc, argv) = {
-      let vec: Vec<*const _> = args
-        .into_iter()
-        .map(|item| ManuallyDrop::new(item.into_fxr_bytes()).as_ptr())
-        .collect();
-      let mut vec = ManuallyDrop::new(vec);
-      let len = vec.len() as _;
-      let ptr = vec.as_mut_ptr();
-
-      (len, ptr)
-    };
+    // hostfxr copies the arguments and parameters during initialization
+    let (_args, mut argv) = into_argv(args);
+    let parameters = parameters.map(HostFxrParameters::into_raw);
 
     let mut handle = null_mut();
-    let flag = unsafe { symbol(argc, argv, parameters.into_fxr_ptr(), &mut handle) };
+    clear_last_error();
+    let flag = unsafe {
+      symbol(
+        argv.len() as _,
+        argv.as_mut_ptr(),
+        RawParameters::as_ptr(&parameters),
+        &mut handle,
+      )
+    };
 
     HostFxrError::from_status(flag)?;
 
@@ -108,7 +114,7 @@ impl<'lib> InitializeConfigSymbol<'lib> {
     &self,
     runtime_config: R,
     parameters: Option<HostFxrParameters>,
-  ) -> HostFxrResult<Option<NonNull<c_void>>>
+  ) -> HostFxrResult<Option<(NonNull<c_void>, HostFxrContextKind)>>
   where
     R: IntoFxrBytes<char_t>,
   {
@@ -118,18 +124,21 @@ impl<'lib> InitializeConfigSymbol<'lib> {
       .lift_option()
       .expect("Symbol `hostfxr_initialize_for_runtime_config` not found");
 
+    let parameters = parameters.map(HostFxrParameters::into_raw);
+
     let mut handle = null_mut();
+    clear_last_error();
     let flag = unsafe {
       symbol(
         runtime_config.into_fxr_ptr(),
-        parameters.into_fxr_ptr(),
+        RawParameters::as_ptr(&parameters),
         &mut handle,
       )
     };
 
-    HostFxrError::from_status(flag)?;
+    let kind = HostFxrContextKind::from_status(flag)?;
 
-    Ok(NonNull::new(handle))
+    Ok(NonNull::new(handle).map(|handle| (handle, kind)))
   }
 }
 
@@ -173,6 +182,7 @@ impl<'lib> SetRuntimePropertyValueSymbol<'lib> {
     let value = value.into_fxr_bytes();
     let value = value.as_ptr();
 
+    clear_last_error();
     let flag = unsafe { symbol(handle.as_mut(), name, value) };
 
     HostFxrError::from_status(flag)?;
@@ -212,6 +222,7 @@ impl<'lib> GetRuntimePropertyValueSymbol<'lib> {
     let name = name.as_ptr();
     let mut value: *const char_t = null();
 
+    clear_last_error();
     let flag = unsafe { symbol(handle.as_mut(), name, &mut value as *mut *const _) };
 
     HostFxrError::from_status(flag)?;
@@ -238,6 +249,15 @@ impl<'lib> GetRuntimePropertiesSymbol<'lib> {
     &self,
     handle: &mut NonNull<c_void>,
   ) -> HostFxrResult<HashMap<String, String>> {
+    self.get(unsafe { handle.as_mut() })
+  }
+
+  /// Get all the runtime properties of the running runtime
+  pub fn invoke_active(&self) -> HostFxrResult<HashMap<String, String>> {
+    self.get(null_mut())
+  }
+
+  fn get(&self, handle: *mut c_void) -> HostFxrResult<HashMap<String, String>> {
     let symbol = self
       .0
       .clone()
@@ -250,9 +270,10 @@ impl<'lib> GetRuntimePropertiesSymbol<'lib> {
     let mut keys = vec![null(); count as usize];
     let mut values = vec![null(); count as usize];
 
+    clear_last_error();
     let flag = unsafe {
       symbol(
-        handle.as_mut(),
+        handle,
         &mut count as *mut _,
         keys.as_mut_ptr(),
         values.as_mut_ptr(),
@@ -285,15 +306,16 @@ impl<'lib> RunAppSymbol<'lib> {
     Ok(Self(unsafe { library.get(b"hostfxr_run_app")? }))
   }
 
-  /// Run app
-  pub fn invoke(&self, handle: &mut NonNull<c_void>) -> HostFxrResult<()> {
+  /// Run app, returning its exit code
+  pub fn invoke(&self, handle: &mut NonNull<c_void>) -> HostFxrResult<ExitCode> {
     let symbol = self
       .0
       .clone()
       .lift_option()
       .expect("Symbol `hostfxr_run_app` not found");
 
-    HostFxrError::from_status(unsafe { symbol(handle.as_mut()) })
+    clear_last_error();
+    ExitCode::from_status(unsafe { symbol(handle.as_mut()) })
   }
 }
 
@@ -323,6 +345,7 @@ impl<'lib> GetRuntimeDelegateSymbol<'lib> {
 
     let mut delegate = MaybeUninit::<D::Fn>::uninit();
     let delegate_ptr = delegate.as_mut_ptr() as *mut _ as *mut *mut _;
+    clear_last_error();
     let flag = unsafe { symbol(handle.as_mut(), D::KIND, delegate_ptr) };
 
     HostFxrError::from_status(flag)?;
@@ -331,6 +354,398 @@ impl<'lib> GetRuntimeDelegateSymbol<'lib> {
   }
 }
 
+/// Safely wraps `hostfxr_set_error_writer` calls
+#[derive(Debug, Clone)]
+pub struct SetErrorWriterSymbol<'lib>(
+  Symbol<'lib, dotnet_hostfxr_sys::hostfxr_set_error_writer_fn>,
+);
+
+impl<'lib> SetErrorWriterSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe { library.get(b"hostfxr_set_error_writer")? }))
+  }
+
+  /// Set the error writer of the calling thread, returning the previous one
+  ///
+  /// Passing `None` restores the default behaviour of writing to stderr.
+  pub fn invoke(
+    &self,
+    writer: dotnet_hostfxr_sys::hostfxr_error_writer_fn,
+  ) -> dotnet_hostfxr_sys::hostfxr_error_writer_fn {
+    let symbol = self
+      .0
+      .clone()
+      .lift_option()
+      .expect("Symbol `hostfxr_set_error_writer` not found");
+
+    unsafe { symbol(writer) }
+  }
+}
+
+/// Safely wraps `hostfxr_get_dotnet_environment_info` calls
+///
+/// The export was added in .NET 6, on older hosts calls fail with
+/// [`HostFxrError::ImportNotFound`].
+#[derive(Debug, Clone)]
+pub struct GetDotnetEnvironmentInfoSymbol<'lib>(
+  Option<Symbol<'lib, dotnet_hostfxr_sys::hostfxr_get_dotnet_environment_info_fn>>,
+);
+
+impl<'lib> GetDotnetEnvironmentInfoSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe {
+      library.get(b"hostfxr_get_dotnet_environment_info").ok()
+    }))
+  }
+
+  /// Get information about the installed SDKs and frameworks
+  ///
+  /// # Arguments
+  /// * `dotnet_root` - Root of the .NET installation to inspect, or `None` to use the
+  ///   installation hostfxr was loaded from
+  pub fn invoke<R>(&self, dotnet_root: Option<R>) -> HostFxrResult<DotnetEnvironmentInfo>
+  where
+    R: IntoFxrBytes<char_t>,
+  {
+    unsafe extern "C" fn result(
+      info: *const dotnet_hostfxr_sys::hostfxr_dotnet_environment_info,
+      context: *mut c_void,
+    ) {
+      let context = &mut *(context as *mut Option<DotnetEnvironmentInfo>);
+      if let Some(info) = info.as_ref() {
+        *context = Some(DotnetEnvironmentInfo::from_native(info));
+      }
+    }
+
+    let symbol = self
+      .0
+      .clone()
+      .and_then(|symbol| symbol.lift_option())
+      .ok_or(HostFxrError::ImportNotFound)?;
+
+    let dotnet_root = dotnet_root.map(IntoFxrBytes::into_fxr_bytes);
+    let dotnet_root = dotnet_root.as_ref().map_or(null(), |root| root.as_ptr());
+
+    let mut info: Option<DotnetEnvironmentInfo> = None;
+    clear_last_error();
+    let flag = unsafe {
+      symbol(
+        dotnet_root,
+        null_mut(),
+        Some(result),
+        &mut info as *mut _ as *mut c_void,
+      )
+    };
+
+    HostFxrError::from_status(flag)?;
+
+    info.ok_or(HostFxrError::Unexpected(flag))
+  }
+}
+
+/// Safely wraps `hostfxr_resolve_sdk2` calls
+#[derive(Debug, Clone)]
+pub struct ResolveSdkSymbol<'lib>(
+  Symbol<'lib, dotnet_hostfxr_sys::hostfxr_resolve_sdk2_fn>,
+);
+
+impl<'lib> ResolveSdkSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe { library.get(b"hostfxr_resolve_sdk2")? }))
+  }
+
+  /// Resolve the SDK the `dotnet` executable in `exe_dir` would use when run from
+  /// `working_dir`, taking `global.json` into account
+  ///
+  /// # Arguments
+  /// * `exe_dir` - Directory of the `dotnet` executable, SDKs are looked up in its `sdk`
+  ///   sub-directory
+  /// * `working_dir` - Directory `global.json` is searched from
+  /// * `flags` - Resolution flags
+  pub fn invoke<E, W>(
+    &self,
+    exe_dir: E,
+    working_dir: W,
+    flags: ResolveSdkFlags,
+  ) -> HostFxrResult<ResolvedSdk>
+  where
+    E: IntoFxrBytes<char_t>,
+    W: IntoFxrBytes<char_t>,
+  {
+    // The result callback has no context argument, results are collected per thread
+    thread_local! {
+      static RESULT: RefCell<ResolvedSdk> = RefCell::new(ResolvedSdk::default());
+    }
+
+    unsafe extern "C" fn result(
+      key: dotnet_hostfxr_sys::hostfxr_resolve_sdk2_result_key_t,
+      value: *const char_t,
+    ) {
+      if value.is_null() {
+        return;
+      }
+
+      let value = Some(value.into_fxr_string().into());
+      RESULT.with(|result| {
+        let mut result = result.borrow_mut();
+        match key {
+          dotnet_hostfxr_sys::hostfxr_resolve_sdk2_result_key_t_resolved_sdk_dir => {
+            result.sdk_dir = value
+          }
+          dotnet_hostfxr_sys::hostfxr_resolve_sdk2_result_key_t_global_json_path => {
+            result.global_json_path = value
+          }
+          _ => {}
+        }
+      });
+    }
+
+    let symbol = self
+      .0
+      .clone()
+      .lift_option()
+      .expect("Symbol `hostfxr_resolve_sdk2` not found");
+
+    let exe_dir = exe_dir.into_fxr_bytes();
+    let working_dir = working_dir.into_fxr_bytes();
+
+    RESULT.with(|result| result.take());
+    clear_last_error();
+    let flag = unsafe {
+      symbol(
+        exe_dir.as_ptr(),
+        working_dir.as_ptr(),
+        flags.bits(),
+        Some(result),
+      )
+    };
+    let result = RESULT.with(|result| result.take());
+
+    HostFxrError::from_status(flag)?;
+
+    Ok(result)
+  }
+}
+
+/// Safely wraps `hostfxr_get_available_sdks` calls
+#[derive(Debug, Clone)]
+pub struct GetAvailableSdksSymbol<'lib>(
+  Symbol<'lib, dotnet_hostfxr_sys::hostfxr_get_available_sdks_fn>,
+);
+
+impl<'lib> GetAvailableSdksSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe { library.get(b"hostfxr_get_available_sdks")? }))
+  }
+
+  /// Get the directories of the SDKs installed next to the `dotnet` executable in
+  /// `exe_dir`, sorted by ascending version
+  pub fn invoke<E>(&self, exe_dir: E) -> HostFxrResult<Vec<PathBuf>>
+  where
+    E: IntoFxrBytes<char_t>,
+  {
+    // The result callback has no context argument, results are collected per thread
+    thread_local! {
+      static RESULT: RefCell<Vec<PathBuf>> = const { RefCell::new(Vec::new()) };
+    }
+
+    unsafe extern "C" fn result(sdk_count: i32, sdk_dirs: *const *const char_t) {
+      if sdk_dirs.is_null() {
+        return;
+      }
+
+      let sdk_dirs = std::slice::from_raw_parts(sdk_dirs, sdk_count.max(0) as usize);
+      let sdk_dirs = sdk_dirs
+        .iter()
+        .filter(|dir| !dir.is_null())
+        .map(|dir| PathBuf::from(dir.into_fxr_string()));
+
+      RESULT.with(|result| result.borrow_mut().extend(sdk_dirs));
+    }
+
+    let symbol = self
+      .0
+      .clone()
+      .lift_option()
+      .expect("Symbol `hostfxr_get_available_sdks` not found");
+
+    let exe_dir = exe_dir.into_fxr_bytes();
+
+    RESULT.with(|result| result.take());
+    clear_last_error();
+    let flag = unsafe { symbol(exe_dir.as_ptr(), Some(result)) };
+    let result = RESULT.with(|result| result.take());
+
+    HostFxrError::from_status(flag)?;
+
+    Ok(result)
+  }
+}
+
+/// Copy each argument into a HostFxr API compatible string, the returned buffers must
+/// outlive the pointers
+fn into_argv<A, I>(args: A) -> (Vec<Vec<char_t>>, Vec<*const char_t>)
+where
+  A: IntoIterator<Item = I>,
+  I: IntoFxrBytes<char_t>,
+{
+  let args: Vec<_> = args.into_iter().map(IntoFxrBytes::into_fxr_bytes).collect();
+  let argv = args.iter().map(|arg| arg.as_ptr()).collect();
+
+  (args, argv)
+}
+
+/// Safely wraps `hostfxr_main` calls
+#[derive(Debug, Clone)]
+pub struct MainSymbol<'lib>(Symbol<'lib, dotnet_hostfxr_sys::hostfxr_main_fn>);
+
+impl<'lib> MainSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe { library.get(b"hostfxr_main")? }))
+  }
+
+  /// Run a managed app the way the `dotnet` muxer does, returning its exit code
+  ///
+  /// # Arguments
+  /// * `args` - Command line, starting with the path of the host executable
+  pub fn invoke<A, I>(&self, args: A) -> HostFxrResult<ExitCode>
+  where
+    A: IntoIterator<Item = I>,
+    I: IntoFxrBytes<char_t>,
+  {
+    let symbol = self
+      .0
+      .clone()
+      .lift_option()
+      .expect("Symbol `hostfxr_main` not found");
+
+    let (_args, mut argv) = into_argv(args);
+
+    clear_last_error();
+    ExitCode::from_status(unsafe { symbol(argv.len() as _, argv.as_mut_ptr()) })
+  }
+}
+
+/// Safely wraps `hostfxr_main_startupinfo` calls
+#[derive(Debug, Clone)]
+pub struct MainStartupInfoSymbol<'lib>(
+  Symbol<'lib, dotnet_hostfxr_sys::hostfxr_main_startupinfo_fn>,
+);
+
+impl<'lib> MainStartupInfoSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe { library.get(b"hostfxr_main_startupinfo")? }))
+  }
+
+  /// Run a managed app as an app host, returning its exit code
+  ///
+  /// # Arguments
+  /// * `args` - Command line, starting with the path of the host executable
+  /// * `host_path` - Path of the host executable
+  /// * `dotnet_root` - Root of the .NET installation to run the app with
+  /// * `app_path` - Path of the managed app assembly
+  pub fn invoke<A, I, H, D, P>(
+    &self,
+    args: A,
+    host_path: H,
+    dotnet_root: D,
+    app_path: P,
+  ) -> HostFxrResult<ExitCode>
+  where
+    A: IntoIterator<Item = I>,
+    I: IntoFxrBytes<char_t>,
+    H: IntoFxrBytes<char_t>,
+    D: IntoFxrBytes<char_t>,
+    P: IntoFxrBytes<char_t>,
+  {
+    let symbol = self
+      .0
+      .clone()
+      .lift_option()
+      .expect("Symbol `hostfxr_main_startupinfo` not found");
+
+    let (_args, mut argv) = into_argv(args);
+    let host_path = host_path.into_fxr_bytes();
+    let dotnet_root = dotnet_root.into_fxr_bytes();
+    let app_path = app_path.into_fxr_bytes();
+
+    clear_last_error();
+    ExitCode::from_status(unsafe {
+      symbol(
+        argv.len() as _,
+        argv.as_mut_ptr(),
+        host_path.as_ptr(),
+        dotnet_root.as_ptr(),
+        app_path.as_ptr(),
+      )
+    })
+  }
+}
+
+/// Safely wraps `hostfxr_main_bundle_startupinfo` calls
+///
+/// The export was added in .NET 5, on older hosts calls fail with
+/// [`HostFxrError::ImportNotFound`].
+#[derive(Debug, Clone)]
+pub struct MainBundleStartupInfoSymbol<'lib>(
+  Option<Symbol<'lib, dotnet_hostfxr_sys::hostfxr_main_bundle_startupinfo_fn>>,
+);
+
+impl<'lib> MainBundleStartupInfoSymbol<'lib> {
+  pub fn new(library: &'lib Library) -> HostFxrResult<Self> {
+    Ok(Self(unsafe {
+      library.get(b"hostfxr_main_bundle_startupinfo").ok()
+    }))
+  }
+
+  /// Run a managed app bundled into a single-file host, returning its exit code
+  ///
+  /// # Arguments
+  /// * `args` - Command line, starting with the path of the host executable
+  /// * `host_path` - Path of the host executable
+  /// * `dotnet_root` - Root of the .NET installation to run the app with
+  /// * `app_path` - Path of the managed app assembly
+  /// * `bundle_header_offset` - Offset of the bundle header in the host executable
+  pub fn invoke<A, I, H, D, P>(
+    &self,
+    args: A,
+    host_path: H,
+    dotnet_root: D,
+    app_path: P,
+    bundle_header_offset: i64,
+  ) -> HostFxrResult<ExitCode>
+  where
+    A: IntoIterator<Item = I>,
+    I: IntoFxrBytes<char_t>,
+    H: IntoFxrBytes<char_t>,
+    D: IntoFxrBytes<char_t>,
+    P: IntoFxrBytes<char_t>,
+  {
+    let symbol = self
+      .0
+      .clone()
+      .and_then(|symbol| symbol.lift_option())
+      .ok_or(HostFxrError::ImportNotFound)?;
+
+    let (_args, mut argv) = into_argv(args);
+    let host_path = host_path.into_fxr_bytes();
+    let dotnet_root = dotnet_root.into_fxr_bytes();
+    let app_path = app_path.into_fxr_bytes();
+
+    clear_last_error();
+    ExitCode::from_status(unsafe {
+      symbol(
+        argv.len() as _,
+        argv.as_mut_ptr(),
+        host_path.as_ptr(),
+        dotnet_root.as_ptr(),
+        app_path.as_ptr(),
+        bundle_header_offset,
+      )
+    })
+  }
+}
+
 /// Safely wraps `hostfxr_close` calls
 #[derive(Debug, Clone)]
 pub struct CloseSymbol<'lib>(Symbol<'lib, dotnet_hostfxr_sys::hostfxr_close_fn>);
@@ -344,7 +759,10 @@ impl<'lib> CloseSymbol<'lib> {
   pub fn invoke(&self, handle: &mut NonNull<c_void>) -> HostFxrResult<()> {
     let symbol = self.0.clone().lift_option();
     match symbol {
-      Some(symbol) => HostFxrError::from_status(unsafe { symbol(handle.as_mut()) }),
+      Some(symbol) => {
+        clear_last_error();
+        HostFxrError::from_status(unsafe { symbol(handle.as_mut()) })
+      }
       None => Ok(()),
     }
   }
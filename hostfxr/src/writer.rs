@@ -0,0 +1,122 @@
+use crate::{string::IntoFxrString, symbol::SetErrorWriterSymbol};
+use dotnet_hostfxr_sys::{char_t, hostfxr_error_writer_fn};
+use std::{
+  cell::RefCell,
+  marker::PhantomData,
+  panic::{catch_unwind, AssertUnwindSafe},
+};
+
+type ErrorWriter = Box<dyn FnMut(&str)>;
+
+// hostfxr keeps one error writer per thread, so do we
+thread_local! {
+  static WRITER: RefCell<Option<ErrorWriter>> = const { RefCell::new(None) };
+  static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Take the text written since the last hosting call on this thread
+pub(crate) fn take_last_error() -> Option<String> {
+  LAST_ERROR.with(|last| last.borrow_mut().take())
+}
+
+/// Discard text written during previous calls, so failures only carry their own message
+pub(crate) fn clear_last_error() {
+  LAST_ERROR.with(|last| last.borrow_mut().take());
+}
+
+/// Native error writer forwarding to the closure installed on the calling thread
+unsafe extern "C" fn write_error(message: *const char_t) {
+  if message.is_null() {
+    return;
+  }
+
+  let message = message.into_fxr_string();
+
+  WRITER.with(|writer| {
+    // Writers calling back into hostfxr can be re-entered, the nested message is dropped
+    if let Ok(mut writer) = writer.try_borrow_mut() {
+      if let Some(writer) = writer.as_mut() {
+        // Unwinding into hostfxr is undefined behaviour
+        catch_unwind(AssertUnwindSafe(|| writer(&message))).ok();
+      }
+    }
+  });
+
+  LAST_ERROR.with(|last| {
+    let mut last = last.borrow_mut();
+    match last.as_mut() {
+      Some(last) => {
+        last.push('\n');
+        last.push_str(&message);
+      }
+      None => *last = Some(message),
+    }
+  });
+}
+
+/// Restores the previous error writer of the current thread when dropped
+///
+/// Returned by [`HostFxrLibrary::set_error_writer`](crate::HostFxrLibrary::set_error_writer).
+#[must_use = "the previous error writer is restored when the guard is dropped"]
+pub struct ErrorWriterGuard<'lib> {
+  symbol: SetErrorWriterSymbol<'lib>,
+  previous: Option<ErrorWriter>,
+  previous_native: hostfxr_error_writer_fn,
+  // Error writers are per thread, restoring on another thread would be wrong
+  phantom: PhantomData<*const ()>,
+}
+
+impl<'lib> ErrorWriterGuard<'lib> {
+  pub(crate) fn install<F>(symbol: &SetErrorWriterSymbol<'lib>, writer: F) -> Self
+  where
+    F: FnMut(&str) + 'static,
+  {
+    let previous = WRITER.with(|current| current.replace(Some(Box::new(writer))));
+    let previous_native = symbol.invoke(Some(write_error));
+
+    Self {
+      symbol: symbol.clone(),
+      previous,
+      previous_native,
+      phantom: PhantomData,
+    }
+  }
+}
+
+impl Drop for ErrorWriterGuard<'_> {
+  fn drop(&mut self) {
+    let previous = self.previous.take();
+
+    WRITER.with(|current| *current.borrow_mut() = previous);
+    self.symbol.invoke(self.previous_native);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{clear_last_error, write_error};
+  use crate::{string::IntoFxrBytes, HostFxrError};
+  use dotnet_hostfxr_sys::char_t;
+
+  fn write(message: &str) {
+    let message: Vec<char_t> = message.into_fxr_bytes();
+    unsafe { write_error(message.as_ptr()) }
+  }
+
+  #[test]
+  fn test_last_error() {
+    // Written during a call that succeeded
+    write("stale");
+
+    clear_last_error();
+    write("first");
+    write("second");
+
+    match HostFxrError::from_status(0x80008081u32 as _) {
+      Err(HostFxrError::InvalidArgFailure(message)) => {
+        assert_eq!(message.as_deref(), Some("first\nsecond"))
+      }
+      other => panic!("unexpected {:?}", other),
+    }
+  }
+}
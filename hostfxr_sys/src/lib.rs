@@ -1,3 +1,91 @@
 #![allow(non_camel_case_types)]
 #![allow(non_upper_case_globals)]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+// Items introduced after the hostfxr version the bindings are generated from, resolving
+// the matching exports fails on older hosts
+
+/// `hostfxr_dotnet_environment_sdk_info`, added in .NET 6
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct hostfxr_dotnet_environment_sdk_info {
+  pub size: size_t,
+  pub version: *const char_t,
+  pub path: *const char_t,
+}
+
+/// `hostfxr_dotnet_environment_framework_info`, added in .NET 6
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct hostfxr_dotnet_environment_framework_info {
+  pub size: size_t,
+  pub name: *const char_t,
+  pub version: *const char_t,
+  pub path: *const char_t,
+}
+
+/// `hostfxr_dotnet_environment_info`, added in .NET 6
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct hostfxr_dotnet_environment_info {
+  pub size: size_t,
+  pub hostfxr_version: *const char_t,
+  pub hostfxr_commit_hash: *const char_t,
+  pub sdk_count: size_t,
+  pub sdks: *const hostfxr_dotnet_environment_sdk_info,
+  pub framework_count: size_t,
+  pub frameworks: *const hostfxr_dotnet_environment_framework_info,
+}
+
+pub type hostfxr_get_dotnet_environment_info_result_fn = ::std::option::Option<
+  unsafe extern "C" fn(
+    info: *const hostfxr_dotnet_environment_info,
+    result_context: *mut ::std::os::raw::c_void,
+  ),
+>;
+
+/// `hostfxr_get_dotnet_environment_info`, added in .NET 6
+pub type hostfxr_get_dotnet_environment_info_fn = ::std::option::Option<
+  unsafe extern "C" fn(
+    dotnet_root: *const char_t,
+    reserved: *mut ::std::os::raw::c_void,
+    result: hostfxr_get_dotnet_environment_info_result_fn,
+    result_context: *mut ::std::os::raw::c_void,
+  ) -> i32,
+>;
+
+// Exported by hostfxr but not declared in hostfxr.h
+
+pub type hostfxr_resolve_sdk2_flags_t = i32;
+pub const hostfxr_resolve_sdk2_flags_t_disallow_prerelease: hostfxr_resolve_sdk2_flags_t =
+  0x1;
+
+pub type hostfxr_resolve_sdk2_result_key_t = i32;
+pub const hostfxr_resolve_sdk2_result_key_t_resolved_sdk_dir:
+  hostfxr_resolve_sdk2_result_key_t = 0;
+pub const hostfxr_resolve_sdk2_result_key_t_global_json_path:
+  hostfxr_resolve_sdk2_result_key_t = 1;
+
+pub type hostfxr_resolve_sdk2_result_fn = ::std::option::Option<
+  unsafe extern "C" fn(key: hostfxr_resolve_sdk2_result_key_t, value: *const char_t),
+>;
+
+pub type hostfxr_resolve_sdk2_fn = ::std::option::Option<
+  unsafe extern "C" fn(
+    exe_dir: *const char_t,
+    working_dir: *const char_t,
+    flags: hostfxr_resolve_sdk2_flags_t,
+    result: hostfxr_resolve_sdk2_result_fn,
+  ) -> i32,
+>;
+
+pub type hostfxr_get_available_sdks_result_fn = ::std::option::Option<
+  unsafe extern "C" fn(sdk_count: i32, sdk_dirs: *const *const char_t),
+>;
+
+pub type hostfxr_get_available_sdks_fn = ::std::option::Option<
+  unsafe extern "C" fn(
+    exe_dir: *const char_t,
+    result: hostfxr_get_available_sdks_result_fn,
+  ) -> i32,
+>;
@@ -0,0 +1,303 @@
+use crate::{
+  marshal::{
+    Blittable, ManagedOption, Marshal, MarshalError, MarshalFrom, MarshalOption,
+    MarshalTo,
+  },
+  types::TypeId,
+};
+use std::{
+  ffi::c_void,
+  mem::ManuallyDrop,
+  ops::Deref,
+  ptr::{self, null_mut},
+  slice,
+};
+
+/// Frees the buffer of a [`RawArray`], called with its `handle`
+pub type ReleaseArray = unsafe extern "C" fn(handle: *mut c_void);
+
+/// Native representation of arrays, mirrors `RawArray` in the bridge
+///
+/// Strings use the same representation with their UTF-16 code units as elements.
+///
+/// `ptr` points to `len` elements in their managed representation, a null `ptr` is a null
+/// reference.  Whoever allocates the buffer fills in `release`, dropping the array passes
+/// `handle` to it:
+/// * Arrays passed to managed code are only borrowed for the duration of the call, the
+///   bridge copies the elements and rust frees the buffer once the call returns.
+/// * Arrays returned from managed code are owned by rust, the elements are moved out and
+///   the buffer is released back through the bridge.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawArray<T> {
+  ptr: *mut T,
+  len: u32,
+  handle: *mut c_void,
+  release: Option<ReleaseArray>,
+}
+
+impl<T> RawArray<T> {
+  pub fn null() -> Self {
+    Self {
+      ptr: null_mut(),
+      len: 0,
+      handle: null_mut(),
+      release: None,
+    }
+  }
+
+  /// Construct array pointing to `len` elements at `ptr` without owning them, fails if
+  /// `len` does not fit in a managed array
+  ///
+  /// # Safety
+  /// Assumes `ptr` points to `len` elements that outlive the array
+  pub unsafe fn borrowed(ptr: *const T, len: usize) -> Result<Self, MarshalError> {
+    Ok(Self {
+      ptr: ptr as _,
+      len: managed_len(len)?,
+      ..Self::null()
+    })
+  }
+
+  /// Construct array taking ownership of `vec`, which is freed when the array is dropped,
+  /// fails if its length does not fit in a managed array
+  pub fn owned(vec: Vec<T>) -> Result<Self, MarshalError> {
+    unsafe extern "C" fn release<T>(handle: *mut c_void) {
+      drop(Box::from_raw(handle as *mut Vec<T>));
+    }
+
+    let len = managed_len(vec.len())?;
+    let mut vec = Box::new(vec);
+
+    Ok(Self {
+      ptr: vec.as_mut_ptr(),
+      len,
+      handle: Box::into_raw(vec) as _,
+      release: Some(release::<T>),
+    })
+  }
+
+  pub fn is_null(&self) -> bool {
+    self.ptr.is_null()
+  }
+
+  pub fn len(&self) -> usize {
+    self.len as _
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  pub fn as_slice(&self) -> &[T] {
+    if self.ptr.is_null() {
+      &[]
+    } else {
+      unsafe { slice::from_raw_parts(self.ptr, self.len as _) }
+    }
+  }
+
+  /// Move the elements out of an array returned from managed code, `None` if it is a null
+  /// reference
+  ///
+  /// The buffer is released afterwards without dropping the elements again, arrays
+  /// constructed with [`RawArray::owned`] must not be passed here.
+  pub(crate) fn into_vec(self) -> Option<Vec<T>> {
+    if self.is_null() {
+      return None;
+    }
+
+    let vec = (0..self.len())
+      .map(|i| unsafe { ptr::read(self.ptr.add(i)) })
+      .collect();
+
+    Some(vec)
+  }
+}
+
+/// Managed arrays are indexed by `int`, longer arrays would be truncated by the bridge
+fn managed_len(len: usize) -> Result<u32, MarshalError> {
+  if len > i32::MAX as usize {
+    return Err(MarshalError::TooLong(len));
+  }
+
+  Ok(len as _)
+}
+
+impl<T> Drop for RawArray<T> {
+  fn drop(&mut self) {
+    if let Some(release) = self.release.take() {
+      unsafe { release(self.handle) }
+    }
+  }
+}
+
+/// Arrays are null references when `None`
+impl<T> ManagedOption<RawArray<T>> for RawArray<T> {
+  fn id(inner: TypeId) -> TypeId {
+    inner
+  }
+
+  fn none() -> Self {
+    Self::null()
+  }
+
+  fn some(managed: RawArray<T>) -> Self {
+    managed
+  }
+
+  fn into_option(self) -> Option<RawArray<T>> {
+    if self.is_null() {
+      None
+    } else {
+      Some(self)
+    }
+  }
+}
+
+/// Array returned from managed code that is read in place instead of copied
+///
+/// The bridge pins arrays of primitives for as long as the slice is alive, other arrays are
+/// copied into a native buffer first.
+#[derive(Debug)]
+pub struct ManagedSlice<T>(RawArray<T>);
+
+impl<T> Deref for ManagedSlice<T> {
+  type Target = [T];
+
+  fn deref(&self) -> &Self::Target {
+    self.0.as_slice()
+  }
+}
+
+impl<T: Blittable> Marshal for ManagedSlice<T> {
+  type Managed = RawArray<T>;
+
+  fn id() -> TypeId {
+    TypeId::Array(Box::new(T::id()))
+  }
+}
+
+impl<T: Blittable> MarshalFrom for ManagedSlice<T> {
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    if from.is_null() {
+      return Err(MarshalError::NullReference);
+    }
+
+    Ok(Self(from))
+  }
+}
+
+impl<T: Blittable> MarshalOption for ManagedSlice<T> {
+  type Optional = Self::Managed;
+}
+
+impl<M: Marshal> Marshal for &[M] {
+  type Managed = RawArray<M::Managed>;
+
+  fn id() -> TypeId {
+    TypeId::Array(Box::new(M::id()))
+  }
+}
+
+impl<M> MarshalTo for &[M]
+where
+  M: MarshalTo + Clone,
+{
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    // Only `Blittable` elements can prove to be their own managed representation, pass
+    // the slice as is
+    if M::blittable().is_some() {
+      return unsafe { RawArray::borrowed(self.as_ptr() as *const _, self.len()) };
+    }
+
+    let vec = self
+      .iter()
+      .cloned()
+      .map(MarshalTo::marshal_to)
+      .collect::<Result<Vec<_>, _>>()?;
+
+    RawArray::owned(vec)
+  }
+}
+
+impl<M: Marshal> MarshalOption for &[M] {
+  type Optional = Self::Managed;
+}
+
+impl<M: Marshal> Marshal for Vec<M> {
+  type Managed = RawArray<M::Managed>;
+
+  fn id() -> TypeId {
+    TypeId::Array(Box::new(M::id()))
+  }
+}
+
+impl<M: Marshal> MarshalOption for Vec<M> {
+  type Optional = Self::Managed;
+}
+
+impl<M: MarshalTo> MarshalTo for Vec<M> {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    // See `&[M]`, the cast is the identity for blittable elements
+    if M::blittable().is_some() {
+      let mut vec = ManuallyDrop::new(self);
+      let vec = unsafe {
+        Vec::from_raw_parts(vec.as_mut_ptr() as *mut _, vec.len(), vec.capacity())
+      };
+
+      return RawArray::owned(vec);
+    }
+
+    let vec = self
+      .into_iter()
+      .map(MarshalTo::marshal_to)
+      .collect::<Result<Vec<_>, _>>()?;
+
+    RawArray::owned(vec)
+  }
+}
+
+impl<M: MarshalFrom> MarshalFrom for Vec<M> {
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    // Every element is moved out before converting any, so elements after a failed one
+    // are still dropped
+    from
+      .into_vec()
+      .ok_or(MarshalError::NullReference)?
+      .into_iter()
+      .map(M::marshal_from)
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::RawArray;
+  use crate::marshal::{MarshalError, MarshalTo};
+  use std::ptr::NonNull;
+
+  #[test]
+  fn test_marshal_to() {
+    let values = [1, 2, 3];
+    let borrowed = (&values[..]).marshal_to().unwrap();
+
+    assert_eq!(borrowed.as_slice().as_ptr(), values.as_ptr());
+
+    let owned = vec![true, false].marshal_to().unwrap();
+
+    assert_eq!(owned.as_slice(), &[true, false]);
+    assert!(RawArray::<u8>::null().as_slice().is_empty());
+  }
+  #[test]
+  fn test_too_long() {
+    let ptr = NonNull::<u8>::dangling().as_ptr();
+    let len = i32::MAX as usize;
+
+    assert!(unsafe { RawArray::borrowed(ptr, len) }.is_ok());
+    assert!(matches!(
+      unsafe { RawArray::borrowed(ptr, len + 1) },
+      Err(MarshalError::TooLong(_))
+    ));
+  }
+}
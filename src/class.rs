@@ -1,11 +1,13 @@
 use crate::{
+  error::{RuntimeError, RuntimeResult},
   gc::GcHandle,
-  marshal::{Marshal, MarshalError, MarshalFrom, MarshalTo},
+  marshal::{Marshal, MarshalError, MarshalFrom, MarshalOption, MarshalTo},
+  method::{Binding, MemberKind, Method},
   runtime::Global,
   types::{Type, TypeId},
   Runtime,
 };
-use std::{ffi::c_void, marker::PhantomData};
+use std::marker::PhantomData;
 
 #[derive(Debug)]
 pub struct Class<R: Runtime = Global> {
@@ -21,21 +23,131 @@ impl<R: Runtime> Class<R> {
     }
   }
 
-  pub fn get_field<M: MarshalFrom>(&self, name: &str) -> Result<M, R::Error> {
-    todo!()
+  pub fn handle(&self) -> &GcHandle<(), R> {
+    &self.handle
   }
 
-  pub fn get_property<M: MarshalFrom>(&self, name: &str) -> Result<M, R::Error> {
-    todo!()
+  /// Allocate a new normal handle to the same object
+  ///
+  /// Fails if the bridge can not allocate the handle, or with
+  /// [`MarshalError::NullReference`] if this is a weak handle whose target was collected.
+  pub fn try_clone(&self) -> RuntimeResult<Self, R> {
+    let handle = self
+      .handle
+      .normal()
+      .map_err(RuntimeError::Runtime)?
+      .ok_or(MarshalError::NullReference)?;
+
+    Ok(Self::new(handle))
+  }
+
+  pub fn get_field<M: MarshalFrom>(&self, name: &str) -> RuntimeResult<M, R> {
+    self.get_member(name, MemberKind::Field, Binding::Instance)
+  }
+
+  pub fn set_field<M: MarshalTo>(&self, name: &str, value: M) -> RuntimeResult<(), R> {
+    self.set_member(name, value, MemberKind::Field, Binding::Instance)
+  }
+
+  pub fn get_property<M: MarshalFrom>(&self, name: &str) -> RuntimeResult<M, R> {
+    self.get_member(name, MemberKind::Property, Binding::Instance)
+  }
+
+  pub fn set_property<M: MarshalTo>(&self, name: &str, value: M) -> RuntimeResult<(), R> {
+    self.set_member(name, value, MemberKind::Property, Binding::Instance)
+  }
+
+  pub fn get_type(&self) -> RuntimeResult<Type<R>, R> {
+    let ty = self.call::<fn() -> Class<R>, _>("GetType", ())?;
+
+    Ok(unsafe { Type::new_unchecked(ty) })
+  }
+
+  /// Call instance method `name` on this object
+  ///
+  /// The overload is picked using the argument and return types of `M`, virtual methods
+  /// and interface implementations are dispatched to the implementation of this object.
+  pub fn call<M, A>(&self, name: &str, args: A) -> RuntimeResult<M::Ret, R>
+  where
+    M: Method<A>,
+    M::Fn: Method<A>,
+  {
+    self.call_member::<M, A>(name, args, Binding::Instance)
+  }
+
+  /// Call method `name` of this object, or of the type this object represents for static
+  /// methods
+  pub(crate) fn call_member<M, A>(
+    &self,
+    name: &str,
+    args: A,
+    binding: Binding,
+  ) -> RuntimeResult<M::Ret, R>
+  where
+    M: Method<A>,
+    M::Fn: Method<A>,
+  {
+    let rt = R::get().map_err(RuntimeError::Runtime)?;
+    let method = rt
+      .member::<M, A>(&self.handle, name, MemberKind::Method, binding)
+      .map_err(RuntimeError::Runtime)?;
+
+    method.call(args)
   }
 
-  pub fn get_type(&mut self) -> Result<Type<R>, R::Error> {
-    todo!()
+  /// Read field or property `name` of this object, or of the type this object represents
+  /// for static members
+  pub(crate) fn get_member<M: MarshalFrom>(
+    &self,
+    name: &str,
+    kind: MemberKind,
+    binding: Binding,
+  ) -> RuntimeResult<M, R> {
+    let rt = R::get().map_err(RuntimeError::Runtime)?;
+    let getter = rt
+      .member::<fn() -> M, _>(&self.handle, name, kind, binding)
+      .map_err(RuntimeError::Runtime)?;
+
+    getter.call(())
+  }
+
+  /// Write field or property `name` of this object, or of the type this object represents
+  /// for static members
+  pub(crate) fn set_member<M: MarshalTo>(
+    &self,
+    name: &str,
+    value: M,
+    kind: MemberKind,
+    binding: Binding,
+  ) -> RuntimeResult<(), R> {
+    let rt = R::get().map_err(RuntimeError::Runtime)?;
+    let setter = rt
+      .member::<fn(M), _>(&self.handle, name, kind, binding)
+      .map_err(RuntimeError::Runtime)?;
+
+    setter.call((value,))
   }
 }
 
+impl<R: Runtime> Clone for Class<R> {
+  /// Allocates a new normal handle to the same object, see [`Class::try_clone`]
+  ///
+  /// # Panics
+  /// Panics if the handle can not be allocated or the target of a weak handle was collected
+  fn clone(&self) -> Self {
+    match self.try_clone() {
+      Ok(class) => class,
+      Err(RuntimeError::Runtime(err)) => panic!("Failed to clone GcHandle: {}", err),
+      Err(RuntimeError::Exception(_)) => {
+        unreachable!("Allocating handles does not throw")
+      }
+    }
+  }
+}
+
+// Handles are passed by value and released once the call returns
 impl<R: Runtime> Marshal for Class<R> {
-  type Managed = *mut c_void;
+  type Managed = Option<GcHandle<(), R>>;
 
   fn id() -> TypeId {
     TypeId::Object
@@ -44,12 +156,35 @@ impl<R: Runtime> Marshal for Class<R> {
 
 impl<R: Runtime> MarshalTo for Class<R> {
   fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
-    todo!()
+    Ok(Some(self.handle))
+  }
+}
+
+impl<R: Runtime> MarshalTo for &Class<R>
+where
+  R::Error: 'static,
+{
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    let handle = self
+      .handle
+      .normal()
+      .map_err(|err| MarshalError::Custom(Box::new(err)))?
+      .ok_or(MarshalError::NullReference)?;
+
+    Ok(Some(handle))
   }
 }
 
 impl<R: Runtime> MarshalFrom for Class<R> {
   fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
-    todo!()
+    from.map(Class::new).ok_or(MarshalError::NullReference)
   }
 }
+
+impl<R: Runtime> MarshalOption for Class<R> {
+  type Optional = Self::Managed;
+}
+
+impl<R: Runtime> MarshalOption for &Class<R> {
+  type Optional = Self::Managed;
+}
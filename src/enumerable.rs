@@ -0,0 +1,323 @@
+use crate::{
+  class::Class,
+  error::RuntimeResult,
+  gc::GcHandle,
+  marshal::{Marshal, MarshalError, MarshalFrom, MarshalOption, MarshalTo},
+  runtime::Global,
+  types::TypeId,
+  Runtime,
+};
+use std::{
+  ffi::c_void,
+  fmt,
+  marker::PhantomData,
+  panic::{catch_unwind, AssertUnwindSafe},
+  ptr::null_mut,
+};
+
+/// Managed `IEnumerable<T>` iterated from rust
+///
+/// The enumerator is created on the first call to [`Iterator::next`] and disposed once it
+/// is exhausted, fails or the iterator is dropped.  Calls are resolved through the
+/// interfaces of the enumerable, so explicit interface implementations work as well.
+pub struct ManagedEnumerable<T, R: Runtime = Global> {
+  enumerable: Class<R>,
+  enumerator: Option<Class<R>>,
+  done: bool,
+  phantom: PhantomData<T>,
+}
+
+impl<T, R: Runtime> ManagedEnumerable<T, R> {
+  /// Wrap an object implementing `IEnumerable<T>`, iterating fails otherwise
+  pub fn new(enumerable: Class<R>) -> Self {
+    Self {
+      enumerable,
+      enumerator: None,
+      done: false,
+      phantom: Default::default(),
+    }
+  }
+
+  /// The managed `IEnumerable<T>`
+  pub fn as_class(&self) -> &Class<R> {
+    &self.enumerable
+  }
+
+  fn dispose(&mut self) {
+    if let Some(enumerator) = self.enumerator.take() {
+      // Disposing is best effort, the enumerator is released either way
+      enumerator
+        .call::<fn(), _>("System.IDisposable.Dispose", ())
+        .ok();
+    }
+  }
+}
+
+impl<T: MarshalFrom, R: Runtime> ManagedEnumerable<T, R> {
+  fn move_next(&mut self) -> RuntimeResult<Option<T>, R> {
+    let enumerator = match &self.enumerator {
+      Some(enumerator) => enumerator,
+      None => {
+        let enumerator = self.enumerable.call::<fn() -> Class<R>, _>(
+          "System.Collections.Generic.IEnumerable`1.GetEnumerator",
+          (),
+        )?;
+
+        self.enumerator.get_or_insert(enumerator)
+      }
+    };
+
+    if !enumerator
+      .call::<fn() -> bool, _>("System.Collections.IEnumerator.MoveNext", ())?
+    {
+      return Ok(None);
+    }
+
+    let current =
+      enumerator.get_property("System.Collections.Generic.IEnumerator`1.Current")?;
+
+    Ok(Some(current))
+  }
+}
+
+impl<T: MarshalFrom, R: Runtime> Iterator for ManagedEnumerable<T, R> {
+  type Item = RuntimeResult<T, R>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+
+    let next = self.move_next().transpose();
+    if !matches!(next, Some(Ok(_))) {
+      self.done = true;
+      self.dispose();
+    }
+
+    next
+  }
+}
+
+impl<T, R: Runtime> Drop for ManagedEnumerable<T, R> {
+  fn drop(&mut self) {
+    self.dispose();
+  }
+}
+
+impl<T: Marshal, R: Runtime> Marshal for ManagedEnumerable<T, R> {
+  type Managed = Option<GcHandle<(), R>>;
+
+  fn id() -> TypeId {
+    TypeId::Enumerable(Box::new(T::id()))
+  }
+}
+
+impl<T: Marshal, R: Runtime> MarshalOption for ManagedEnumerable<T, R> {
+  type Optional = Self::Managed;
+}
+
+impl<T: MarshalFrom, R: Runtime> MarshalFrom for ManagedEnumerable<T, R> {
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    Class::marshal_from(from).map(Self::new)
+  }
+}
+
+/// Result of advancing a [`RawIterator`], mirrors `NextStatus` in the bridge
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NextStatus {
+  /// The iterator is exhausted
+  Done,
+  /// `item` points to the next element
+  Item,
+  /// Marshalling the next element failed or the iterator panicked
+  Failed,
+}
+
+/// Advances the iterator owned by `state`, pointing `item` to the managed representation of
+/// the next element which stays valid until the next call
+pub type NextFn =
+  unsafe extern "C" fn(state: *mut c_void, item: *mut *const c_void) -> NextStatus;
+
+/// Native representation of rust iterators passed to managed code, mirrors `RawIterator` in
+/// the bridge
+///
+/// The bridge takes ownership of the iterator by clearing `state` and releases it once the
+/// managed enumerator is disposed or collected.  Iterators the bridge did not take are
+/// released when this is dropped.
+#[repr(C)]
+pub struct RawIterator {
+  state: *mut c_void,
+  next: NextFn,
+  release: unsafe extern "C" fn(state: *mut c_void),
+}
+
+impl RawIterator {
+  fn new<I>(iter: I) -> Self
+  where
+    I: Iterator + Send + 'static,
+    I::Item: MarshalTo,
+  {
+    struct State<I: Iterator>
+    where
+      I::Item: Marshal,
+    {
+      iter: I,
+      current: Option<<I::Item as Marshal>::Managed>,
+    }
+
+    unsafe extern "C" fn next<I>(
+      state: *mut c_void,
+      item: *mut *const c_void,
+    ) -> NextStatus
+    where
+      I: Iterator,
+      I::Item: MarshalTo,
+    {
+      let state = &mut *(state as *mut State<I>);
+      state.current = None;
+
+      // Unwinding into managed code is undefined behaviour, panics fail the enumeration
+      let next = catch_unwind(AssertUnwindSafe(|| {
+        state.iter.next().map(MarshalTo::marshal_to)
+      }));
+
+      match next {
+        Ok(None) => NextStatus::Done,
+        Err(_) | Ok(Some(Err(_))) => NextStatus::Failed,
+        Ok(Some(Ok(current))) => {
+          *item = state.current.get_or_insert(current) as *const _ as *const c_void;
+          NextStatus::Item
+        }
+      }
+    }
+
+    unsafe extern "C" fn release<I>(state: *mut c_void)
+    where
+      I: Iterator,
+      I::Item: Marshal,
+    {
+      drop(Box::from_raw(state as *mut State<I>));
+    }
+
+    let state = Box::new(State {
+      iter,
+      current: None,
+    });
+
+    Self {
+      state: Box::into_raw(state) as _,
+      next: next::<I>,
+      release: release::<I>,
+    }
+  }
+}
+
+impl Drop for RawIterator {
+  fn drop(&mut self) {
+    let state = std::mem::replace(&mut self.state, null_mut());
+    if !state.is_null() {
+      unsafe { (self.release)(state) }
+    }
+  }
+}
+
+impl fmt::Debug for RawIterator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("RawIterator")
+      .field("state", &self.state)
+      .finish()
+  }
+}
+
+/// Rust iterator passed to managed code as a lazy `IEnumerable<T>`
+///
+/// Elements are produced on demand while managed code enumerates, which can happen after
+/// the call returned and on other threads.  The enumerable can only be enumerated once.
+///
+/// # Example
+/// ```no_run
+/// use dotnet::{enumerable::IterEnumerable, runtime::Global, Runtime};
+/// use std::ops::Range;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let rt = Global::get()?;
+/// let sum = rt
+///   .method::<fn(IterEnumerable<Range<i32>>) -> i32, _>("System.Linq.Enumerable.Sum")?
+///   .call((IterEnumerable::new(0..4),))?;
+///
+/// assert_eq!(sum, 6);
+/// # Ok(())
+/// # }
+/// ```
+pub struct IterEnumerable<I>(I);
+
+impl<I: Iterator> IterEnumerable<I> {
+  pub fn new<T: IntoIterator<IntoIter = I>>(iter: T) -> Self {
+    Self(iter.into_iter())
+  }
+}
+
+impl<I> Marshal for IterEnumerable<I>
+where
+  I: Iterator,
+  I::Item: Marshal,
+{
+  type Managed = RawIterator;
+
+  fn id() -> TypeId {
+    TypeId::Enumerable(Box::new(I::Item::id()))
+  }
+}
+
+impl<I> MarshalTo for IterEnumerable<I>
+where
+  I: Iterator + Send + 'static,
+  I::Item: MarshalTo,
+{
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    Ok(RawIterator::new(self.0))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{IterEnumerable, NextStatus};
+  use crate::marshal::MarshalTo;
+  use std::ptr::null;
+
+  #[test]
+  fn test_raw_iterator() {
+    let raw = IterEnumerable::new(vec![1, 2]).marshal_to().unwrap();
+    let mut item = null();
+
+    for expected in &[1, 2] {
+      assert_eq!(
+        unsafe { (raw.next)(raw.state, &mut item) },
+        NextStatus::Item
+      );
+      assert_eq!(unsafe { *(item as *const i32) }, *expected);
+    }
+
+    assert_eq!(
+      unsafe { (raw.next)(raw.state, &mut item) },
+      NextStatus::Done
+    );
+  }
+
+  #[test]
+  fn test_raw_iterator_panic() {
+    let raw = IterEnumerable::new((0..2).map(|i| if i == 0 { i } else { panic!() }))
+      .marshal_to()
+      .unwrap();
+    let mut item = null();
+
+    assert_eq!(
+      unsafe { (raw.next)(raw.state, &mut item) },
+      NextStatus::Item
+    );
+    assert_eq!(
+      unsafe { (raw.next)(raw.state, &mut item) },
+      NextStatus::Failed
+    );
+  }
+}
@@ -1,11 +1,57 @@
-use crate::{exception::Exception, runtime::Global, Runtime};
+use crate::{exception::Exception, marshal::MarshalError, runtime::Global, Runtime};
+use std::{error::Error, fmt};
 
-#[repr(C, u8)]
-pub enum RuntimeResult<T, R: Runtime = Global> {
-  Ok(T),
-  Err(RuntimeError<R>),
-}
+pub type RuntimeResult<T, R = Global> = Result<T, RuntimeError<R>>;
 
 pub enum RuntimeError<R: Runtime = Global> {
+  /// Managed code threw an exception
   Exception(Exception<R>),
+  /// Resolving, marshalling or calling into the runtime failed
+  Runtime(R::Error),
+}
+
+impl<R: Runtime> From<MarshalError> for RuntimeError<R> {
+  fn from(err: MarshalError) -> Self {
+    Self::Runtime(err.into())
+  }
+}
+
+impl<R: Runtime> fmt::Debug for RuntimeError<R>
+where
+  R::Error: fmt::Debug,
+{
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Exception(exception) => f
+        .debug_tuple("Exception")
+        .field(&exception.handle().as_ptr())
+        .finish(),
+      Self::Runtime(err) => f.debug_tuple("Runtime").field(err).finish(),
+    }
+  }
+}
+
+impl<R: Runtime> fmt::Display for RuntimeError<R> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      // Describing the exception calls back into the runtime which can fail as well
+      Self::Exception(exception) => match (exception.type_name(), exception.message()) {
+        (Ok(name), Ok(message)) => write!(f, "{}: {}", name, message),
+        _ => write!(f, "Managed exception was thrown"),
+      },
+      Self::Runtime(err) => err.fmt(f),
+    }
+  }
+}
+
+impl<R: Runtime> Error for RuntimeError<R>
+where
+  R::Error: 'static,
+{
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      Self::Exception(_) => None,
+      Self::Runtime(err) => Some(err),
+    }
+  }
 }
@@ -1,17 +1,42 @@
-use crate::{class::Class, gc::GcHandle, runtime::Global, Runtime};
+use crate::{class::Class, error::RuntimeResult, gc::GcHandle, runtime::Global, Runtime};
 use std::ops::Deref;
 
+/// Managed `System.Exception` thrown while calling into the runtime
 #[derive(Debug)]
 pub struct Exception<R: Runtime = Global>(Class<R>);
 
 impl<R: Runtime> Exception<R> {
+  /// # Safety
+  /// Assumes `handle` points to a `System.Exception`
   pub unsafe fn new_unchecked(handle: GcHandle<(), R>) -> Self {
     Self(Class::new(handle))
   }
 
-  pub fn message(&self) -> Result<String, R::Error> {
+  /// Full name of the exception type, e.g. `System.ArgumentException`
+  pub fn type_name(&self) -> RuntimeResult<String, R> {
+    self.get_type()?.get_full_name()
+  }
+
+  pub fn message(&self) -> RuntimeResult<String, R> {
     self.get_property("Message")
   }
+
+  /// Stack trace of the managed frames the exception unwound through, `None` if it was
+  /// never thrown
+  pub fn stack_trace(&self) -> RuntimeResult<Option<String>, R> {
+    self.get_property("StackTrace")
+  }
+
+  pub fn hresult(&self) -> RuntimeResult<i32, R> {
+    self.get_property("HResult")
+  }
+
+  /// Exception that caused this one, if any
+  pub fn inner_exception(&self) -> RuntimeResult<Option<Exception<R>>, R> {
+    let inner = self.get_property::<Option<Class<R>>>("InnerException")?;
+
+    Ok(inner.map(Self))
+  }
 }
 
 impl<R: Runtime> Deref for Exception<R> {
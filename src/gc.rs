@@ -1,17 +1,81 @@
 use crate::{runtime::Global, Runtime};
-use std::{marker::PhantomData, ptr::NonNull};
+use std::{marker::PhantomData, mem::ManuallyDrop, ptr::NonNull};
 
+/// Mirrors `System.Runtime.InteropServices.GCHandleType`
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GcHandleKind {
+  /// Tracks an object without keeping it alive
+  Weak = 0,
+  /// Tracks an object without keeping it alive, until after finalization
+  WeakTrackResurrection = 1,
+  /// Keeps an object alive
+  Normal = 2,
+  /// Keeps an object alive and prevents the GC from moving it
+  Pinned = 3,
+}
+
+/// Owned handle to a managed object, freed when dropped
 #[derive(Debug)]
+#[repr(transparent)]
 pub struct GcHandle<T, R: Runtime = Global> {
   ptr: NonNull<T>,
   phantom: PhantomData<R>,
 }
 
+impl<T, R: Runtime> GcHandle<T, R> {
+  /// Construct handle from raw bridge handle, taking ownership of it
+  ///
+  /// # Safety
+  /// Assumes `ptr` was allocated by the bridge and is not owned by anything else
+  pub unsafe fn from_raw(ptr: NonNull<T>) -> Self {
+    Self {
+      ptr,
+      phantom: Default::default(),
+    }
+  }
+
+  /// Release ownership of the raw bridge handle without freeing it
+  pub fn into_raw(self) -> NonNull<T> {
+    ManuallyDrop::new(self).ptr
+  }
+
+  pub fn as_ptr(&self) -> *mut T {
+    self.ptr.as_ptr()
+  }
+
+  /// Allocate a new handle of `kind` to the object this handle points to
+  ///
+  /// Returns `None` if the object has been collected, which can only happen when this is a
+  /// weak handle.
+  pub fn alloc(&self, kind: GcHandleKind) -> Result<Option<Self>, R::Error> {
+    let handle = R::get()?.alloc(&self.ptr, kind)?;
+
+    Ok(handle.map(|handle| unsafe { Self::from_raw(handle) }))
+  }
+
+  /// Allocate a handle that keeps the object alive
+  pub fn normal(&self) -> Result<Option<Self>, R::Error> {
+    self.alloc(GcHandleKind::Normal)
+  }
+
+  /// Allocate a handle that does not keep the object alive
+  pub fn weak(&self) -> Result<Option<Self>, R::Error> {
+    self.alloc(GcHandleKind::Weak)
+  }
+
+  /// Allocate a handle that keeps the object alive and at a fixed address
+  pub fn pinned(&self) -> Result<Option<Self>, R::Error> {
+    self.alloc(GcHandleKind::Pinned)
+  }
+}
+
 impl<T, R: Runtime> Drop for GcHandle<T, R> {
   fn drop(&mut self) {
+    // Panicking here would abort while already unwinding, a handle the bridge refuses to
+    // release is leaked instead
     if let Ok(rt) = R::get() {
-      rt.release(&mut self.ptr)
-        .expect("Failed to release GcHandle")
+      rt.release(&mut self.ptr).ok();
     }
   }
 }
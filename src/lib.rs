@@ -1,26 +1,76 @@
+pub mod array;
 pub mod class;
+pub mod enumerable;
 pub mod error;
 pub mod exception;
 pub mod gc;
 pub mod marshal;
 pub mod method;
 pub mod runtime;
+pub mod string;
 pub mod types;
 
-use method::Method;
+use class::Class;
+use error::{RuntimeError, RuntimeResult};
+use gc::{GcHandle, GcHandleKind};
+use marshal::MarshalError;
+use method::{Arguments, Binding, Function, MemberKind, Method};
 use std::{error::Error, ptr::NonNull};
+use types::Type;
 
 pub trait Runtime: Sized {
-  type Error: Error;
+  type Error: Error + From<MarshalError>;
 
   fn get() -> Result<Self, Self::Error>;
 
-  fn method<M, A>(&self, path: &str) -> Result<&M, Self::Error>
+  /// Resolve a static managed method from `Namespace.Type.Method`, optionally assembly
+  /// qualified, matching the argument and return types of `M`
+  fn method<M, A>(&self, path: &str) -> Result<Function<'_, M, A, Self>, Self::Error>
   where
     M: Method<A>,
     // Not required for this to work but, prevents returning non-plain `fn(..) -> ..` fns by
     // requiring return to be `Fn(..) -> ..`
     M::Fn: Method<A>;
 
+  /// Resolve a member of the object `target` points to, or of the `System.Type` it points
+  /// to for static members, matching the argument and return types of `M`
+  ///
+  /// Fields and properties are read using `fn() -> T` and written using `fn(T)`.
+  fn member<'a, M, A>(
+    &'a self,
+    target: &'a GcHandle<(), Self>,
+    name: &str,
+    kind: MemberKind,
+    binding: Binding,
+  ) -> Result<Function<'a, M, A, Self>, Self::Error>
+  where
+    M: Method<A>,
+    M::Fn: Method<A>;
+
+  /// Find a managed type by its, optionally assembly qualified, name
+  fn get_type(&self, name: &str) -> Result<Type<Self>, Self::Error>;
+
+  /// Create a new instance of the type `name`, picking the constructor using the types of
+  /// `args`
+  fn new_object<A: Arguments>(
+    &self,
+    name: &str,
+    args: A,
+  ) -> RuntimeResult<Class<Self>, Self> {
+    self
+      .get_type(name)
+      .map_err(RuntimeError::Runtime)?
+      .construct(args)
+  }
+
+  /// Allocate a new handle of `kind` to the target of `handle`, returns `None` if the
+  /// target has been collected
+  fn alloc<T>(
+    &self,
+    handle: &NonNull<T>,
+    kind: GcHandleKind,
+  ) -> Result<Option<NonNull<T>>, Self::Error>;
+
+  /// Free `handle`
   fn release<T>(&self, handle: &mut NonNull<T>) -> Result<(), Self::Error>;
 }
@@ -1,8 +1,16 @@
 use crate::types::TypeId;
-use std::{mem::ManuallyDrop, slice::from_raw_parts};
+use std::{fmt, marker::PhantomData, mem::MaybeUninit};
 
 #[derive(thiserror::Error, Debug)]
 pub enum MarshalError {
+  #[error("Unexpected null reference")]
+  NullReference,
+  #[error("String is not valid unicode")]
+  InvalidUnicode,
+  #[error("Character `{0}` does not fit in a single UTF-16 code unit")]
+  NonBmpChar(char),
+  #[error("Array of {0} elements is too long for a managed array")]
+  TooLong(usize),
   #[error(transparent)]
   Custom(#[from] Box<dyn std::error::Error>),
 }
@@ -11,8 +19,10 @@ pub trait Marshal {
   type Managed: Sized;
 
   fn id() -> TypeId;
-  fn blittable() -> bool {
-    false
+
+  /// Proof that values are passed as is, only [`Blittable`] types can return one
+  fn blittable() -> Option<IsBlittable<Self>> {
+    None
   }
 }
 
@@ -24,6 +34,36 @@ impl<M: Marshal> Marshal for &M {
   }
 }
 
+/// Types that are their own managed representation, arrays of them can be read in place
+///
+/// # Safety
+/// Implementors must match the layout of the managed type their [`TypeId`] maps to
+pub unsafe trait Blittable: Marshal<Managed = Self> + Copy {}
+
+/// Proof that `T` implements [`Blittable`], so `T` and `T::Managed` are the same type
+///
+/// Generic code can not name the bound without specialization, [`Marshal::blittable`]
+/// returns this instead so `&[T]` can be reinterpreted as `&[T::Managed]`.
+pub struct IsBlittable<T: ?Sized>(PhantomData<*const T>);
+
+impl<T: Blittable> IsBlittable<T> {
+  pub fn new() -> Self {
+    Self(PhantomData)
+  }
+}
+
+impl<T: Blittable> Default for IsBlittable<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: ?Sized> fmt::Debug for IsBlittable<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("IsBlittable")
+  }
+}
+
 pub trait MarshalTo: Marshal {
   fn marshal_to(self) -> Result<Self::Managed, MarshalError>;
 }
@@ -41,11 +81,13 @@ macro_rules! marshal_blittable {
         $type_id
       }
 
-      fn blittable() -> bool {
-        true
+      fn blittable() -> Option<IsBlittable<Self>> {
+        Some(IsBlittable::new())
       }
     }
 
+    unsafe impl Blittable for $type {}
+
     impl MarshalTo for $type {
       #[inline]
       fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
@@ -62,6 +104,29 @@ macro_rules! marshal_blittable {
   };
 }
 
+/// Not [`Blittable`], `void` has no `Nullable<void>` or array of it
+impl Marshal for () {
+  type Managed = ();
+
+  fn id() -> TypeId {
+    TypeId::Void
+  }
+}
+
+impl MarshalTo for () {
+  #[inline]
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    Ok(())
+  }
+}
+
+impl MarshalFrom for () {
+  #[inline]
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    Ok(from)
+  }
+}
+
 marshal_blittable!(bool, TypeId::Boolean);
 
 marshal_blittable!(u8, TypeId::Byte);
@@ -82,67 +147,134 @@ marshal_blittable!(usize, TypeId::UInt32);
 #[cfg(target_pointer_width = "64")]
 marshal_blittable!(usize, TypeId::UInt64);
 
-impl<M: Marshal> Marshal for &[M] {
-  type Managed = (*const M::Managed, u32);
+/// Types that can be marshalled as `Option<Self>`
+///
+/// Reference types map `None` to a null reference, value types map to `Nullable<T>`.
+pub trait MarshalOption: Marshal {
+  /// Managed representation of `Option<Self>`, a null reference or [`RawNullable`]
+  type Optional: ManagedOption<Self::Managed>;
+}
 
-  fn id() -> TypeId {
-    TypeId::Array(Box::new(M::id()))
+/// Managed representation of `Option<T>`, where `M` is the managed representation of `T`
+pub trait ManagedOption<M>: Sized {
+  /// Get the `TypeId` of `Option<T>` from the `TypeId` of `T`
+  fn id(inner: TypeId) -> TypeId;
+  fn none() -> Self;
+  fn some(managed: M) -> Self;
+  fn into_option(self) -> Option<M>;
+}
+
+/// Object handles are null references when `None`
+impl<T> ManagedOption<Option<T>> for Option<T> {
+  fn id(inner: TypeId) -> TypeId {
+    inner
+  }
+
+  fn none() -> Self {
+    None
+  }
+
+  fn some(managed: Option<T>) -> Self {
+    managed
+  }
+
+  fn into_option(self) -> Option<Option<T>> {
+    self.map(Some)
   }
 }
 
-impl<M> MarshalTo for &[M]
-where
-  M: MarshalTo + Clone,
-{
-  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
-    let vec = self
-      .iter()
-      .cloned()
-      .map(|val| val.marshal_to())
-      .collect::<Result<Vec<_>, _>>()?;
+/// Native representation of `Nullable<T>`, mirrors `TypeKind.Nullable` in the bridge
+///
+/// `value` is only initialized when `has_value` is set.
+#[repr(C)]
+pub struct RawNullable<T> {
+  has_value: bool,
+  value: MaybeUninit<T>,
+}
 
-    let vec = ManuallyDrop::new(vec);
-    let len = vec.len();
-    let ptr = vec.as_ptr() as *const _;
+impl<T> ManagedOption<T> for RawNullable<T> {
+  fn id(inner: TypeId) -> TypeId {
+    TypeId::Nullable(Box::new(inner))
+  }
 
-    Ok((ptr, len as _))
+  fn none() -> Self {
+    Self {
+      has_value: false,
+      value: MaybeUninit::uninit(),
+    }
   }
+
+  fn some(managed: T) -> Self {
+    Self {
+      has_value: true,
+      value: MaybeUninit::new(managed),
+    }
+  }
+
+  fn into_option(self) -> Option<T> {
+    if self.has_value {
+      Some(unsafe { self.value.assume_init() })
+    } else {
+      None
+    }
+  }
+}
+
+impl<T: Blittable> MarshalOption for T {
+  type Optional = RawNullable<T>;
 }
 
-impl<M: Marshal> Marshal for Vec<M> {
-  type Managed = (*mut M::Managed, u32);
+impl<T: MarshalOption> Marshal for Option<T> {
+  type Managed = T::Optional;
 
   fn id() -> TypeId {
-    TypeId::Array(Box::new(M::id()))
+    T::Optional::id(T::id())
+  }
+}
+
+impl<T: MarshalOption + MarshalTo> MarshalTo for Option<T> {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    match self {
+      Some(value) => Ok(T::Optional::some(value.marshal_to()?)),
+      None => Ok(T::Optional::none()),
+    }
   }
 }
 
-impl<M> MarshalFrom for Vec<M>
-where
-  M: MarshalFrom,
-  M::Managed: Clone,
-{
+impl<T: MarshalOption + MarshalFrom> MarshalFrom for Option<T> {
   fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
-    unsafe { from_raw_parts(from.0, from.1 as _) }
-      .iter()
-      .cloned()
-      .map(M::marshal_from)
-      .collect::<Result<Vec<_>, _>>()
+    from.into_option().map(T::marshal_from).transpose()
   }
 }
 
-impl Marshal for String {
-  type Managed = (*const u8, u32);
+#[cfg(test)]
+mod tests {
+  use super::{Marshal, MarshalFrom, MarshalTo};
+  use crate::{array::RawArray, class::Class, types::TypeId};
 
-  fn id() -> TypeId {
-    TypeId::String
+  #[test]
+  fn test_option_id() {
+    let nullable = TypeId::Nullable(Box::new(TypeId::Int32));
+
+    assert_eq!(Option::<i32>::id(), nullable);
+    assert_eq!(Option::<Class>::id(), TypeId::Object);
+    assert_eq!(Option::<String>::id(), TypeId::String);
+    assert_eq!(
+      Option::<Vec<Option<i32>>>::id(),
+      TypeId::Array(Box::new(nullable))
+    );
   }
-}
 
-impl MarshalFrom for String {
-  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
-    Ok(
-      String::from_utf8_lossy(unsafe { from_raw_parts(from.0, from.1 as _) }).to_string(),
-    )
+  #[test]
+  fn test_option() {
+    let some = Some(42).marshal_to().unwrap();
+    let none = None::<i32>.marshal_to().unwrap();
+
+    assert_eq!(Option::<i32>::marshal_from(some).unwrap(), Some(42));
+    assert_eq!(Option::<i32>::marshal_from(none).unwrap(), None);
+    assert_eq!(
+      Option::<String>::marshal_from(RawArray::null()).unwrap(),
+      None
+    );
   }
 }
@@ -1,7 +1,83 @@
 use crate::{
-  marshal::{MarshalFrom, MarshalTo},
+  class::Class,
+  error::{RuntimeError, RuntimeResult},
+  exception::Exception,
+  gc::GcHandle,
+  marshal::{Marshal, MarshalError, MarshalFrom, MarshalTo},
+  runtime::Global,
   types::TypeId,
+  Runtime,
 };
+use std::{
+  ffi::c_void,
+  marker::PhantomData,
+  mem::MaybeUninit,
+  ptr::{null_mut, NonNull},
+};
+
+/// Native signature shared by every thunk generated by the bridge
+///
+/// `this` is the handle of the target object or null for static members, `args` points to
+/// an array of pointers to each marshalled argument and `ret` points to storage for the
+/// marshalled return value.  Returns a handle to the thrown exception, `ret` is left
+/// untouched in that case, or null on success.
+pub type Thunk = unsafe extern "C" fn(
+  this: *mut c_void,
+  args: *const *mut c_void,
+  ret: *mut c_void,
+) -> *mut c_void;
+
+/// Failed [`Thunk`] call
+#[derive(Debug)]
+pub enum InvokeError {
+  /// Marshalling an argument or the returned value failed
+  Marshal(MarshalError),
+  /// Managed code threw, holds an owned handle to the exception
+  Exception(NonNull<c_void>),
+}
+
+impl From<MarshalError> for InvokeError {
+  fn from(err: MarshalError) -> Self {
+    Self::Marshal(err)
+  }
+}
+
+/// Kind of member resolved using [`Runtime::member`]
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemberKind {
+  Field,
+  Property,
+  Method,
+  Constructor,
+}
+
+/// Whether a member belongs to an object or its type
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+  Instance,
+  Static,
+}
+
+/// Tuple of arguments passed to a [`Thunk`]
+pub trait Arguments: Sized {
+  fn type_ids() -> Vec<TypeId>;
+
+  /// Marshal arguments, call `thunk` on `this` and marshal the returned value back
+  ///
+  /// Arguments are passed by mutable pointer, the callee may write back to them before
+  /// they are dropped, e.g. to take ownership of a
+  /// [`RawIterator`](crate::enumerable::RawIterator).
+  ///
+  /// # Safety
+  /// Assumes `thunk` was resolved by the bridge for these arguments and `R`
+  unsafe fn invoke<R: MarshalFrom>(
+    self,
+    thunk: Thunk,
+    this: *mut c_void,
+  ) -> Result<R, InvokeError>;
+}
 
 pub trait Method<Args> {
   type Fn;
@@ -9,10 +85,110 @@ pub trait Method<Args> {
 
   fn ret_type_id() -> TypeId;
   fn arg_type_ids() -> Vec<TypeId>;
+
+  /// Marshal `args`, call `thunk` on `this` and marshal the returned value back
+  ///
+  /// # Safety
+  /// Assumes `thunk` was resolved by the bridge for this signature
+  unsafe fn invoke(
+    thunk: Thunk,
+    this: *mut c_void,
+    args: Args,
+  ) -> Result<Self::Ret, InvokeError>;
+}
+
+/// Signature of a constructor taking `Args` resolved using [`Runtime::member`]
+pub struct Constructor<R: Runtime = Global>(PhantomData<R>);
+
+impl<R: Runtime, A: Arguments> Method<A> for Constructor<R> {
+  type Fn = Self;
+  type Ret = Class<R>;
+
+  fn ret_type_id() -> TypeId {
+    Class::<R>::id()
+  }
+
+  fn arg_type_ids() -> Vec<TypeId> {
+    A::type_ids()
+  }
+
+  unsafe fn invoke(
+    thunk: Thunk,
+    this: *mut c_void,
+    args: A,
+  ) -> Result<Self::Ret, InvokeError> {
+    args.invoke(thunk, this)
+  }
+}
+
+/// Managed method or member accessor resolved using [`Runtime::method`] or
+/// [`Runtime::member`]
+pub struct Function<'rt, M, A, R: Runtime = Global> {
+  thunk: Thunk,
+  this: *mut c_void,
+  phantom: PhantomData<(&'rt R, M, A)>,
+}
+
+impl<'rt, M, A, R> Function<'rt, M, A, R>
+where
+  M: Method<A>,
+  R: Runtime,
+{
+  /// Construct function from bridge thunk, bound to `this` for instance members
+  ///
+  /// # Safety
+  /// Assumes `thunk` was resolved by the bridge for the signature of `M` and `this`
+  pub unsafe fn from_thunk(thunk: Thunk, this: Option<&'rt GcHandle<(), R>>) -> Self {
+    Self {
+      thunk,
+      this: this.map_or(null_mut(), |this| this.as_ptr() as *mut c_void),
+      phantom: Default::default(),
+    }
+  }
+
+  /// Call managed method with supplied arguments
+  pub fn call(&self, args: A) -> RuntimeResult<M::Ret, R> {
+    unsafe { M::invoke(self.thunk, self.this, args) }.map_err(|err| match err {
+      InvokeError::Marshal(err) => err.into(),
+      InvokeError::Exception(handle) => RuntimeError::Exception(unsafe {
+        Exception::new_unchecked(GcHandle::from_raw(handle.cast()))
+      }),
+    })
+  }
 }
 
 macro_rules! method_impl {
   ($($arg:ident)*) => {
+    impl<$($arg),*> Arguments for ($($arg,)*)
+    where
+      $($arg: MarshalTo),*
+    {
+      fn type_ids() -> Vec<TypeId> {
+        vec![$($arg::id()),*]
+      }
+
+      #[allow(non_snake_case)]
+      unsafe fn invoke<_R: MarshalFrom>(
+        self,
+        thunk: Thunk,
+        this: *mut c_void,
+      ) -> Result<_R, InvokeError> {
+        let ($($arg,)*) = self;
+        $(let mut $arg = $arg.marshal_to()?;)*
+
+        // The bridge may write back to arguments, see `RawIterator`
+        let args: &[*mut c_void] = &[$(&mut $arg as *mut _ as *mut c_void),*];
+        let mut ret = MaybeUninit::<_R::Managed>::uninit();
+
+        let exception = thunk(this, args.as_ptr(), ret.as_mut_ptr() as *mut c_void);
+        if let Some(exception) = NonNull::new(exception) {
+          return Err(InvokeError::Exception(exception));
+        }
+
+        Ok(_R::marshal_from(ret.assume_init())?)
+      }
+    }
+
     impl<_F, _R, $($arg),*> Method<($($arg,)*)> for _F
     where
       _F: Fn($($arg),*) -> _R,
@@ -27,7 +203,15 @@ macro_rules! method_impl {
       }
 
       fn arg_type_ids() -> Vec<TypeId> {
-        vec![$($arg::id()),*]
+        <($($arg,)*)>::type_ids()
+      }
+
+      unsafe fn invoke(
+        thunk: Thunk,
+        this: *mut c_void,
+        args: ($($arg,)*),
+      ) -> Result<_R, InvokeError> {
+        args.invoke(thunk, this)
       }
     }
   };
@@ -1,8 +1,27 @@
-use crate::{types::TypeId, Runtime};
+use crate::{
+  gc::GcHandleKind,
+  method::{Binding, MemberKind, Thunk},
+  types::TypeId,
+  Runtime,
+};
+use once_cell::sync::OnceCell;
 use std::{
-  borrow::Cow, ffi::c_void, marker::PhantomData, mem::ManuallyDrop, ptr::NonNull,
+  convert::TryFrom,
+  env,
+  ffi::c_void,
+  fs,
+  io::{self, Write},
+  marker::PhantomData,
+  mem::size_of,
+  path::{Path, PathBuf},
+  process,
+  ptr::NonNull,
 };
 
+/// Version of the bridge table layout, must match `Bridge.CurrentVersion` in the bridge
+/// assembly
+pub const BRIDGE_VERSION: u32 = 1;
+
 /// Get bridge `GetBridge` method assembly qualified type name
 pub fn get_bridge_type_name() -> &'static str {
   "Bridge, Bridge, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null"
@@ -18,21 +37,226 @@ pub fn get_bridge_delegate_name() -> &'static str {
   "GetBridgeDelegate, Bridge, Version=1.0.0.0, Culture=neutral, PublicKeyToken=null"
 }
 
-/// Get path to bridge assembly
-pub fn get_bridge_assembly_path() -> Cow<'static, str> {
-  cfg_if::cfg_if! {
-    if #[cfg(debug_assertions)] {
-      "bridge/bin/Release/net5.0/bridge.dll".into()
-    } else {
-      compile_error!("Runtime assembly dropping in %TEMP% not implemented")
+/// Bridge assembly built by `build.rs`
+static BRIDGE_ASSEMBLY: &[u8] =
+  include_bytes!(concat!(env!("OUT_DIR"), "/bridge/bridge.dll"));
+
+/// Runtime config the bridge assembly is loaded with
+static BRIDGE_RUNTIME_CONFIG: &[u8] =
+  include_bytes!("../../bridge/bridge.runtimeconfig.json");
+
+/// Get path to bridge assembly, extracting it on first use
+pub fn get_bridge_assembly_path() -> io::Result<PathBuf> {
+  Ok(get_bridge_dir()?.join("bridge.dll"))
+}
+
+/// Get path to bridge runtime config, extracting it on first use
+pub fn get_bridge_runtime_config_path() -> io::Result<PathBuf> {
+  Ok(get_bridge_dir()?.join("bridge.runtimeconfig.json"))
+}
+
+/// Get directory the embedded bridge files are extracted to
+///
+/// Files are extracted once per process to `<cache>/dotnet-bridge/<hash>` where `hash` is
+/// derived from their contents, so binaries embedding different bridges never share files.
+fn get_bridge_dir() -> io::Result<&'static Path> {
+  static DIR: OnceCell<PathBuf> = OnceCell::new();
+
+  DIR
+    .get_or_try_init(|| {
+      let dir = get_cache_dir(&[BRIDGE_ASSEMBLY, BRIDGE_RUNTIME_CONFIG])?;
+
+      extract(&dir.join("bridge.dll"), BRIDGE_ASSEMBLY)?;
+      extract(
+        &dir.join("bridge.runtimeconfig.json"),
+        BRIDGE_RUNTIME_CONFIG,
+      )?;
+
+      Ok(dir)
+    })
+    .map(PathBuf::as_path)
+}
+
+/// Write a generated runtime config next to the bridge files, returning its path
+pub fn write_runtime_config(json: &str) -> io::Result<PathBuf> {
+  let path = get_cache_dir(&[json.as_bytes()])?.join("runtimeconfig.json");
+  extract(&path, json.as_bytes())?;
+
+  Ok(path)
+}
+
+/// Get the per-user directory bridge files are cached in
+///
+/// Extracted files are loaded into the process, so they must not live anywhere other users
+/// can write to.  The user cache directory is preferred over the shared temp directory.
+fn get_cache_root() -> PathBuf {
+  let cache = if cfg!(windows) {
+    env::var_os("LOCALAPPDATA").map(PathBuf::from)
+  } else {
+    env::var_os("XDG_CACHE_HOME")
+      .map(PathBuf::from)
+      .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+  };
+
+  cache
+    .filter(|dir| dir.is_absolute())
+    .unwrap_or_else(env::temp_dir)
+    .join("dotnet-bridge")
+}
+
+/// Create `<cache>/dotnet-bridge/<hash>` where `hash` is derived from `contents`
+fn get_cache_dir(contents: &[&[u8]]) -> io::Result<PathBuf> {
+  let hash = contents
+    .iter()
+    .fold(0xcbf29ce484222325, |hash, bytes| fnv1a(hash, bytes));
+
+  let root = get_cache_root();
+  create_private_dir(&root)?;
+
+  let dir = root.join(format!("{:016x}", hash));
+  create_private_dir(&dir)?;
+
+  Ok(dir)
+}
+
+/// Create `dir` accessible only by the current user, failing if an existing `dir` is not
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+  use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+  fs::DirBuilder::new()
+    .recursive(true)
+    .mode(0o700)
+    .create(dir)?;
+
+  // `create` succeeds for directories planted by someone else, including root
+  let meta = fs::symlink_metadata(dir)?;
+  let owned = meta.uid() == unsafe { libc::geteuid() };
+  if !meta.is_dir() || !owned || meta.permissions().mode() & 0o077 != 0 {
+    return Err(io::Error::new(
+      io::ErrorKind::PermissionDenied,
+      format!("{} is not private to the current user", dir.display()),
+    ));
+  }
+
+  Ok(())
+}
+
+/// Create `dir`, which inherits the access control list of the per-user cache directory
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+  fs::create_dir_all(dir)
+}
+
+/// Write `contents` to `path` unless a previous extraction already did
+///
+/// Existing files are compared byte by byte and replaced if they differ.  Contents are
+/// written to a new file unique to this process and renamed into place, so concurrent
+/// extractions never observe a partially written file.
+fn extract(path: &Path, contents: &[u8]) -> io::Result<()> {
+  if matches!(fs::read(path), Ok(existing) if existing == contents) {
+    return Ok(());
+  }
+
+  let tmp = path.with_extension(format!("{}.tmp", process::id()));
+
+  // A leftover from a crashed process with the same id is removed first, `create_new`
+  // refuses to follow anything planted in its place
+  fs::remove_file(&tmp).ok();
+  fs::OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(&tmp)
+    .and_then(|mut file| file.write_all(contents))?;
+
+  let renamed = fs::rename(&tmp, path);
+  if renamed.is_err() {
+    fs::remove_file(&tmp).ok();
+
+    // Windows refuses to replace a file another process has loaded, that process extracted
+    // the same contents as long as they match
+    if matches!(fs::read(path), Ok(existing) if existing == contents) {
+      return Ok(());
     }
   }
+
+  renamed
+}
+
+/// Convert a length to the integer width the bridge expects, failing instead of truncating
+fn bridge_len<T: TryFrom<usize>>(len: usize) -> Result<T, BridgeError> {
+  T::try_from(len).map_err(|_| BridgeError::TooLong)
+}
+
+/// 64-bit FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+  bytes.iter().fold(hash, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+  })
 }
 
 #[derive(thiserror::Error, Debug, Clone, Copy)]
+#[repr(u8)]
 pub enum BridgeError {
   #[error("Method not found")]
   MethodNotFound,
+  #[error("Type not found")]
+  TypeNotFound,
+  #[error("Multiple methods match the supplied signature")]
+  AmbiguousMatch,
+  #[error("Invalid method path, expected `Namespace.Type.Method`")]
+  InvalidPath,
+  #[error("Handle was not allocated by the bridge or was already released")]
+  InvalidHandle,
+  #[error("Object contains non-blittable data and cannot be pinned")]
+  NotPinnable,
+  #[error("Member not found")]
+  MemberNotFound,
+  #[error("Member type does not match the requested type")]
+  TypeMismatch,
+  #[error("Bridge assembly version does not match the version expected by this crate")]
+  VersionMismatch,
+  #[error("Bridge table size does not match the layout expected by this crate")]
+  LayoutMismatch,
+  #[error("Bridge assembly is missing a capability required by this crate")]
+  MissingCapability,
+  #[error("Name or signature is too long to pass to the bridge")]
+  TooLong,
+}
+
+/// Features implemented by the bridge assembly, mirrors `Capabilities` in the bridge
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities(u64);
+
+impl Capabilities {
+  /// Field, property and instance method access through `GetMember`
+  pub const MEMBERS: Self = Self(1 << 0);
+  /// Constructor resolution through `GetMember`
+  pub const CONSTRUCTORS: Self = Self(1 << 1);
+  /// Thunks return thrown exceptions instead of unwinding
+  pub const EXCEPTIONS: Self = Self(1 << 2);
+  /// Arrays are marshalled as [`RawArray`](crate::array::RawArray)
+  pub const ARRAYS: Self = Self(1 << 3);
+  /// Enumerables are iterated through interface qualified member names and rust iterators
+  /// are marshalled as [`RawIterator`](crate::enumerable::RawIterator)
+  pub const ENUMERABLES: Self = Self(1 << 4);
+
+  /// Capabilities this crate can not work without
+  ///
+  /// Marshalling arrays, strings or iterators with a bridge using another layout corrupts
+  /// memory.
+  pub const REQUIRED: Self = Self(
+    Self::MEMBERS.0
+      | Self::CONSTRUCTORS.0
+      | Self::EXCEPTIONS.0
+      | Self::ARRAYS.0
+      | Self::ENUMERABLES.0,
+  );
+
+  pub fn contains(self, other: Self) -> bool {
+    self.0 & other.0 == other.0
+  }
 }
 
 #[derive(Clone)]
@@ -42,71 +266,174 @@ pub struct Bridge<'rt, R: Runtime> {
 }
 
 impl<'rt, R: Runtime> Bridge<'rt, R> {
-  /// Construct bridge from supplied handle
+  /// Construct bridge from supplied handle, returns `None` if `handle` is null
+  ///
+  /// The version, size and capabilities of the table are validated before anything else is
+  /// read from it.  The table is copied, the bridge assembly keeps ownership of `handle`.
   ///
   /// # Safety
-  /// Assumes that handle points to a struct that represents [`BridgeImpl`]
-  pub unsafe fn from_handle(handle: *mut c_void) -> Option<Self> {
-    let imp = NonNull::new(handle as *mut ffi::BridgeImpl);
-    let imp = match imp {
-      Some(imp) => Box::from_raw(imp.as_ptr()),
-      None => return None,
+  /// Assumes that handle points to a table starting with a [`ffi::BridgeHeader`]
+  pub unsafe fn from_handle(handle: *mut c_void) -> Result<Option<Self>, BridgeError> {
+    let header = match NonNull::new(handle as *mut ffi::BridgeHeader) {
+      Some(header) => header.as_ptr().read(),
+      None => return Ok(None),
     };
 
-    println!("test: {}", imp.test);
+    if header.version != BRIDGE_VERSION {
+      return Err(BridgeError::VersionMismatch);
+    }
+
+    if header.size as usize != size_of::<ffi::BridgeImpl>() {
+      return Err(BridgeError::LayoutMismatch);
+    }
+
+    if !header.capabilities.contains(Capabilities::REQUIRED) {
+      return Err(BridgeError::MissingCapability);
+    }
 
-    Some(Self {
-      imp,
+    Ok(Some(Self {
+      imp: Box::new((handle as *const ffi::BridgeImpl).read()),
       phantom: Default::default(),
-    })
+    }))
+  }
+
+  /// Features implemented by the loaded bridge assembly
+  pub fn capabilities(&self) -> Capabilities {
+    self.imp.header.capabilities
   }
 
-  pub fn get_method<F: Sized>(
+  /// Resolve static method thunk
+  ///
+  /// # Arguments
+  /// * `path` - `Namespace.Type.Method`, optionally followed by `, Assembly`
+  /// * `types` - Argument types followed by the return type
+  pub fn get_method(&self, path: &str, types: &[TypeId]) -> Result<Thunk, BridgeError> {
+    let path_len = bridge_len(path.len())?;
+    let types_len = bridge_len(types.len())?;
+
+    unsafe {
+      (self.imp.get_method)(path.as_ptr(), path_len, types.as_ptr(), types_len)
+        .into_result()
+    }
+  }
+
+  /// Resolve member thunk
+  ///
+  /// # Arguments
+  /// * `target` - Handle of the object, or `System.Type` for static members
+  /// * `name` - Name of the member
+  /// * `types` - Argument types followed by the return type
+  pub fn get_member<T>(
     &self,
-    path: &str,
-    types: Vec<TypeId>,
-  ) -> Result<&F, BridgeError> {
-    let mut path = ManuallyDrop::new(path.to_string());
-    let mut types = ManuallyDrop::new(types);
+    target: *mut T,
+    name: &str,
+    kind: MemberKind,
+    binding: Binding,
+    types: &[TypeId],
+  ) -> Result<Thunk, BridgeError> {
+    let name_len = bridge_len(name.len())?;
+    let types_len = bridge_len(types.len())?;
 
     unsafe {
-      (*self.imp.get_method)(
-        path.as_mut_ptr(),
-        path.len() as _,
-        types.as_mut_ptr(),
-        types.len() as _,
+      (self.imp.get_member)(
+        target as _,
+        name.as_ptr(),
+        name_len,
+        kind,
+        binding,
+        types.as_ptr(),
+        types_len,
       )
       .into_result()
-      .map(|ptr| &*(ptr as *const *mut () as *const F))
     }
   }
 
-  pub fn release<T>(&self, handle: &mut NonNull<T>) -> Result<(), BridgeError> {
-    println!("release(..)");
-    println!("result: {}", unsafe { (*self.imp.release)(0) });
+  /// Find type by its, optionally assembly qualified, name and allocate a handle to its
+  /// `System.Type`
+  pub fn find_type<T>(&self, name: &str) -> Result<NonNull<T>, BridgeError> {
+    let name_len = bridge_len(name.len())?;
+
+    unsafe {
+      (self.imp.find_type)(name.as_ptr(), name_len)
+        .into_result()
+        .and_then(|handle| NonNull::new(handle as _).ok_or(BridgeError::TypeNotFound))
+    }
+  }
+
+  /// Allocate a new handle of `kind` to the target of `handle`
+  pub fn alloc<T>(
+    &self,
+    handle: &NonNull<T>,
+    kind: GcHandleKind,
+  ) -> Result<Option<NonNull<T>>, BridgeError> {
+    unsafe {
+      (self.imp.alloc)(handle.as_ptr() as _, kind)
+        .into_result()
+        .map(|handle| NonNull::new(handle as _))
+    }
+  }
 
-    Ok(())
+  /// Free `handle`
+  pub fn release<T>(&self, handle: &mut NonNull<T>) -> Result<(), BridgeError> {
+    unsafe {
+      (self.imp.release)(handle.as_ptr() as _)
+        .into_result()
+        .map(|_| ())
+    }
   }
 }
 
 mod ffi {
-  use super::BridgeError;
-  use crate::types::TypeId;
+  use super::{BridgeError, Capabilities};
+  use crate::{
+    gc::GcHandleKind,
+    method::{Binding, MemberKind, Thunk},
+    types::TypeId,
+  };
+  use std::ffi::c_void;
 
-  pub type ReleaseFn = unsafe extern "stdcall" fn(handle: usize) -> usize;
-  pub type GetMethodFn = unsafe extern "stdcall" fn(
-    path: *mut u8,
+  pub type ReleaseFn =
+    unsafe extern "C" fn(handle: *mut c_void) -> BridgeResult<*mut c_void>;
+  pub type AllocFn = unsafe extern "C" fn(
+    handle: *mut c_void,
+    kind: GcHandleKind,
+  ) -> BridgeResult<*mut c_void>;
+  pub type GetMethodFn = unsafe extern "C" fn(
+    path: *const u8,
     path_len: u32,
-    types: *mut TypeId,
+    types: *const TypeId,
     types_len: u16,
-  ) -> BridgeResult<*const ()>;
+  ) -> BridgeResult<Thunk>;
+  pub type GetMemberFn = unsafe extern "C" fn(
+    target: *mut c_void,
+    name: *const u8,
+    name_len: u32,
+    kind: MemberKind,
+    binding: Binding,
+    types: *const TypeId,
+    types_len: u16,
+  ) -> BridgeResult<Thunk>;
+  pub type FindTypeFn =
+    unsafe extern "C" fn(name: *const u8, name_len: u32) -> BridgeResult<*mut c_void>;
+
+  /// Leading fields of every bridge table version
+  #[repr(C)]
+  #[derive(Clone, Copy)]
+  pub struct BridgeHeader {
+    pub version: u32,
+    pub size: u32,
+    pub capabilities: Capabilities,
+  }
 
   #[repr(C)]
   #[derive(Clone)]
   pub struct BridgeImpl {
-    pub release: *mut ReleaseFn,
-    pub get_method: *mut GetMethodFn,
-    pub test: i32,
+    pub header: BridgeHeader,
+    pub release: ReleaseFn,
+    pub get_method: GetMethodFn,
+    pub alloc: AllocFn,
+    pub get_member: GetMemberFn,
+    pub find_type: FindTypeFn,
   }
 
   unsafe impl Send for BridgeImpl {}
@@ -128,3 +455,35 @@ mod ffi {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{bridge_len, extract, BridgeError};
+  use std::{env, fs, process};
+
+  #[test]
+  fn test_bridge_len() {
+    assert_eq!(bridge_len::<u16>(3).unwrap(), 3);
+    assert!(matches!(
+      bridge_len::<u16>(u16::MAX as usize + 1),
+      Err(BridgeError::TooLong)
+    ));
+  }
+
+  #[test]
+  fn test_extract_replaces_mismatch() {
+    let dir = env::temp_dir().join(format!("dotnet-bridge-test-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join("bridge.dll");
+    fs::write(&path, b"planted").unwrap();
+
+    extract(&path, b"genuine").unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"genuine");
+
+    extract(&path, b"genuine").unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"genuine");
+
+    fs::remove_dir_all(&dir).ok();
+  }
+}
@@ -1,13 +1,20 @@
 use super::bridge::Bridge;
 use crate::{
-  method::Method,
+  class::Class,
+  gc::{GcHandle, GcHandleKind},
+  marshal::MarshalError,
+  method::{Binding, Function, MemberKind, Method},
   runtime::bridge::{self, BridgeError},
+  types::Type,
   Runtime,
 };
-use dotnet_hostfxr::{HostFxr, HostFxrLibrary};
+use dotnet_hostfxr::HostFxr;
 use once_cell::sync::OnceCell;
 use std::{ffi::c_void, ptr::NonNull, sync::Arc};
 
+mod builder;
+pub use builder::{HostFxrRuntimeBuilder, RollForward};
+
 static CURRENT: OnceCell<HostFxrRuntime> = OnceCell::new();
 
 #[derive(thiserror::Error, Debug)]
@@ -18,6 +25,14 @@ pub enum HostFxrError {
   BridgeNone,
   #[error(transparent)]
   HostFxr(#[from] dotnet_hostfxr::HostFxrError),
+  #[error(transparent)]
+  Marshal(#[from] MarshalError),
+  #[error("Failed to extract bridge assembly")]
+  Extract(#[from] std::io::Error),
+  #[error("Runtime was already initialized")]
+  AlreadyInitialized,
+  #[error("Probing path contains the platform path separator")]
+  InvalidProbingPath,
 }
 
 #[derive(Clone)]
@@ -29,44 +44,68 @@ pub struct HostFxrRuntime<'rt> {
 impl<'rt> Runtime for HostFxrRuntime<'rt> {
   type Error = HostFxrError;
 
+  /// Get the installed runtime, installing one with the default
+  /// [`HostFxrRuntimeBuilder`] settings if there is none
   fn get() -> Result<Self, Self::Error> {
-    if let Some(hostfxr) = CURRENT.get() {
-      return Ok(hostfxr.clone());
-    }
-
-    // Initialize HostFxr
-    let host = HostFxrLibrary::get()?;
-    let host = Arc::new(host.initialize_runtime_config(
-      //
-      "bridge/bridge.runtimeconfig.json",
-      None,
-    )?);
-
-    let bridge = get_bridge(&host)?;
-    let bridge = match bridge {
-      Some(bridge) => bridge,
-      None => return Err(HostFxrError::BridgeNone),
-    };
-
-    // Initialize bridge
-    let runtime = HostFxrRuntime { host, bridge };
-    match CURRENT.try_insert(runtime) {
-      Ok(runtime) => Ok(runtime.clone()),
-      Err((runtime, _)) => Ok(runtime.clone()),
-    }
+    CURRENT
+      .get_or_try_init(|| HostFxrRuntimeBuilder::new().build())
+      .cloned()
   }
 
-  fn method<M, A>(&self, path: &str) -> Result<&M, Self::Error>
+  fn method<M, A>(&self, path: &str) -> Result<Function<'_, M, A, Self>, Self::Error>
   where
     M: Method<A>,
     // Not required for this to work but, prevents returning non-plain `fn(..) -> ..` fns by
     // requiring return to be `Fn(..) -> ..`
     M::Fn: Method<A>,
   {
-    let mut types = M::arg_type_ids().to_vec();
+    let mut types = M::arg_type_ids();
     types.push(M::ret_type_id());
 
-    Ok(self.bridge.get_method(path, types)?)
+    let thunk = self.bridge.get_method(path, &types)?;
+
+    Ok(unsafe { Function::from_thunk(thunk, None) })
+  }
+
+  fn member<'a, M, A>(
+    &'a self,
+    target: &'a GcHandle<(), Self>,
+    name: &str,
+    kind: MemberKind,
+    binding: Binding,
+  ) -> Result<Function<'a, M, A, Self>, Self::Error>
+  where
+    M: Method<A>,
+    M::Fn: Method<A>,
+  {
+    let mut types = M::arg_type_ids();
+    types.push(M::ret_type_id());
+
+    let thunk = self
+      .bridge
+      .get_member(target.as_ptr(), name, kind, binding, &types)?;
+
+    let this = match binding {
+      Binding::Instance => Some(target),
+      Binding::Static => None,
+    };
+
+    Ok(unsafe { Function::from_thunk(thunk, this) })
+  }
+
+  fn get_type(&self, name: &str) -> Result<Type<Self>, Self::Error> {
+    let handle = self.bridge.find_type(name)?;
+    let class = Class::new(unsafe { GcHandle::from_raw(handle) });
+
+    Ok(unsafe { Type::new_unchecked(class) })
+  }
+
+  fn alloc<T>(
+    &self,
+    handle: &NonNull<T>,
+    kind: GcHandleKind,
+  ) -> Result<Option<NonNull<T>>, Self::Error> {
+    Ok(self.bridge.alloc(handle, kind)?)
   }
 
   fn release<T>(&self, handle: &mut NonNull<T>) -> Result<(), Self::Error> {
@@ -80,8 +119,9 @@ fn get_bridge<'rt, H: AsRef<HostFxr<'rt>>, R: Runtime>(
   type GetBridge = unsafe extern "C" fn() -> *mut c_void;
 
   let host = host.as_ref();
-  let bridge: GetBridge = host.load_assembly_and_get_delegate(
-    bridge::get_bridge_assembly_path(),
+  let assembly = bridge::get_bridge_assembly_path()?;
+  let bridge = host.load_assembly_and_get_delegate::<GetBridge, _, _, _, _>(
+    assembly.to_string_lossy(),
     bridge::get_bridge_type_name(),
     bridge::get_bridge_method_name(),
     bridge::get_bridge_delegate_name(),
@@ -89,7 +129,7 @@ fn get_bridge<'rt, H: AsRef<HostFxr<'rt>>, R: Runtime>(
 
   unsafe {
     let bridge = bridge();
-    let bridge = Bridge::from_handle(bridge);
+    let bridge = Bridge::from_handle(bridge)?;
 
     Ok(bridge)
   }
@@ -97,10 +137,18 @@ fn get_bridge<'rt, H: AsRef<HostFxr<'rt>>, R: Runtime>(
 
 #[cfg(test)]
 mod tests {
-  use std::ptr::NonNull;
-
-  use super::HostFxrRuntime;
-  use crate::Runtime;
+  use super::{HostFxrError, HostFxrRuntime};
+  use crate::{
+    array::ManagedSlice,
+    class::Class,
+    enumerable::{IterEnumerable, ManagedEnumerable},
+    error::RuntimeError,
+    marshal::MarshalError,
+    runtime::bridge::BridgeError,
+    string::ManagedStr,
+    Runtime,
+  };
+  use std::{ops::Range, ptr::NonNull, vec::IntoIter};
 
   #[test]
   fn test_get() {
@@ -108,6 +156,243 @@ mod tests {
     let mut test = 0;
     let mut test = NonNull::new(&mut test).unwrap();
 
-    rt.release(&mut test).unwrap();
+    // Handles not allocated by the bridge are rejected instead of freed
+    assert!(rt.release(&mut test).is_err());
+  }
+
+  #[test]
+  fn test_gc_handle() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let version: Class = rt
+      .method::<fn() -> Class, _>("System.Environment.get_Version")
+      .unwrap()
+      .call(())
+      .unwrap();
+
+    let weak = version.handle().weak().unwrap().unwrap();
+    let normal = weak.normal().unwrap();
+
+    assert!(normal.is_some());
+  }
+
+  #[test]
+  fn test_member() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let version: Class = rt
+      .method::<fn() -> Class, _>("System.Environment.get_Version")
+      .unwrap()
+      .call(())
+      .unwrap();
+
+    assert!(version.get_property::<i32>("Major").unwrap() > 0);
+    assert!(matches!(
+      version.get_property::<f64>("Major"),
+      Err(RuntimeError::Runtime(HostFxrError::Bridge(
+        BridgeError::TypeMismatch
+      )))
+    ));
+    assert!(matches!(
+      version.get_field::<i32>("Major"),
+      Err(RuntimeError::Runtime(HostFxrError::Bridge(
+        BridgeError::MemberNotFound
+      )))
+    ));
+  }
+
+  #[test]
+  fn test_call() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let version: Class = rt
+      .method::<fn() -> Class, _>("System.Environment.get_Version")
+      .unwrap()
+      .call(())
+      .unwrap();
+
+    let major = version.get_property::<i32>("Major").unwrap();
+    let minor = version.get_property::<i32>("Minor").unwrap();
+    let formatted = version
+      .call::<fn(i32) -> String, _>("ToString", (2,))
+      .unwrap();
+
+    assert_eq!(formatted, format!("{}.{}", major, minor));
+    assert_eq!(version.get_type().unwrap().get_name().unwrap(), "Version");
+  }
+
+  #[test]
+  fn test_new_object() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let version = rt
+      .new_object("System.Version, System.Runtime", (1, 2))
+      .unwrap();
+
+    assert_eq!(version.get_property::<i32>("Major").unwrap(), 1);
+    assert_eq!(version.get_property::<i32>("Minor").unwrap(), 2);
+    assert!(matches!(
+      rt.new_object("System.Version, System.Runtime", (1.0,)),
+      Err(RuntimeError::Runtime(HostFxrError::Bridge(
+        BridgeError::MemberNotFound
+      )))
+    ));
+    assert!(matches!(
+      rt.get_type("System.DoesNotExist"),
+      Err(HostFxrError::Bridge(BridgeError::TypeNotFound))
+    ));
+  }
+
+  #[test]
+  fn test_exception() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let abs = rt.method::<fn(i32) -> i32, _>("System.Math.Abs").unwrap();
+
+    let exception = match abs.call((i32::MIN,)) {
+      Err(RuntimeError::Exception(exception)) => exception,
+      _ => panic!("Expected OverflowException"),
+    };
+
+    assert_eq!(exception.type_name().unwrap(), "System.OverflowException");
+    assert!(!exception.message().unwrap().is_empty());
+    assert!(exception.stack_trace().unwrap().is_some());
+    assert!(exception.inner_exception().unwrap().is_none());
+
+    // Constructors report exceptions the same way
+    assert!(matches!(
+      rt.new_object("System.Version, System.Runtime", (-1, 0)),
+      Err(RuntimeError::Exception(_))
+    ));
+  }
+
+  #[test]
+  fn test_array() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let get_bytes = rt
+      .method::<fn(i32) -> Vec<u8>, _>("System.BitConverter.GetBytes")
+      .unwrap();
+    let to_int32 = rt
+      .method::<fn(&[u8], i32) -> i32, _>("System.BitConverter.ToInt32")
+      .unwrap();
+
+    let bytes = get_bytes.call((0x01020304,)).unwrap();
+    assert_eq!(bytes.len(), 4);
+    assert_eq!(to_int32.call((&bytes, 0)).unwrap(), 0x01020304);
+
+    let pinned = rt
+      .method::<fn(i32) -> ManagedSlice<u8>, _>("System.BitConverter.GetBytes")
+      .unwrap()
+      .call((0x01020304,))
+      .unwrap();
+    assert_eq!(&*pinned, &bytes[..]);
+  }
+
+  #[test]
+  fn test_string() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let concat = rt
+      .method::<fn(&str, String) -> String, _>("System.String.Concat")
+      .unwrap();
+
+    assert_eq!(
+      concat
+        .call(("h\u{e9}llo ", "\u{1d11e}".to_string()))
+        .unwrap(),
+      "h\u{e9}llo \u{1d11e}"
+    );
+
+    let pinned = rt
+      .method::<fn(&str, &str) -> ManagedStr, _>("System.String.Concat")
+      .unwrap()
+      .call(("a", "b"))
+      .unwrap();
+    assert_eq!(pinned, "ab");
+  }
+
+  #[test]
+  fn test_char() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let join = rt
+      .method::<fn(char, &[&str]) -> String, _>("System.String.Join")
+      .unwrap();
+    let to_upper = rt
+      .method::<fn(char) -> char, _>("System.Char.ToUpperInvariant")
+      .unwrap();
+
+    assert_eq!(join.call((',', &["a", "b"])).unwrap(), "a,b");
+    assert_eq!(to_upper.call(('\u{e9}',)).unwrap(), '\u{c9}');
+    assert!(matches!(
+      to_upper.call(('\u{1d11e}',)),
+      Err(RuntimeError::Runtime(HostFxrError::Marshal(
+        MarshalError::NonBmpChar(_)
+      )))
+    ));
+  }
+
+  #[test]
+  fn test_option() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let get_variable = rt
+      .method::<fn(&str) -> Option<String>, _>(
+        "System.Environment.GetEnvironmentVariable",
+      )
+      .unwrap();
+    let is_null_or_empty = rt
+      .method::<fn(Option<&str>) -> bool, _>("System.String.IsNullOrEmpty")
+      .unwrap();
+
+    assert_eq!(get_variable.call(("DOTNET_RS_UNSET",)).unwrap(), None);
+    assert!(is_null_or_empty.call((None,)).unwrap());
+    assert!(!is_null_or_empty.call((Some("a"),)).unwrap());
+  }
+
+  #[test]
+  fn test_enumerable() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let range = rt
+      .method::<fn(i32, i32) -> ManagedEnumerable<i32>, _>("System.Linq.Enumerable.Range")
+      .unwrap()
+      .call((1, 3))
+      .unwrap();
+
+    assert_eq!(range.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2, 3]);
+
+    let sum = rt
+      .method::<fn(IterEnumerable<Range<i32>>) -> i32, _>("System.Linq.Enumerable.Sum")
+      .unwrap();
+    let sum_nullable = rt
+      .method::<fn(IterEnumerable<IntoIter<Option<i32>>>) -> Option<i32>, _>(
+        "System.Linq.Enumerable.Sum",
+      )
+      .unwrap();
+
+    assert_eq!(sum.call((IterEnumerable::new(0..4),)).unwrap(), 6);
+    assert_eq!(
+      sum_nullable
+        .call((IterEnumerable::new(vec![Some(1), None, Some(3)]),))
+        .unwrap(),
+      Some(4)
+    );
+  }
+
+  #[test]
+  fn test_method() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let max = rt
+      .method::<fn(i32, i32) -> i32, _>("System.Math.Max")
+      .unwrap();
+
+    assert_eq!(max.call((4, 2)).unwrap(), 4);
+  }
+
+  #[test]
+  fn test_overload() {
+    let rt = HostFxrRuntime::get().unwrap();
+    let version = rt
+      .new_object("System.Version, System.Runtime", (1, 2))
+      .unwrap();
+
+    // Picks `Concat(object, object)` over `Concat(string, string)`
+    let concat = rt
+      .method::<fn(&Class, &Class) -> String, _>("System.String.Concat")
+      .unwrap();
+
+    assert_eq!(concat.call((&version, &version)).unwrap(), "1.21.2");
   }
 }
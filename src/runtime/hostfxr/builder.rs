@@ -0,0 +1,259 @@
+use super::{get_bridge, HostFxrError, HostFxrRuntime, CURRENT};
+use crate::runtime::bridge;
+use dotnet_hostfxr::{HostFxrLibrary, HostFxrParameters};
+use std::{env, fmt, path::PathBuf, sync::Arc};
+
+/// Property holding the directories probed for assemblies
+const PROBING_DIRECTORIES: &str = "PROBING_DIRECTORIES";
+
+/// Mirrors the `rollForward` runtimeconfig setting, picks the framework version used when
+/// the requested one is not installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollForward {
+  /// Roll forward to the lowest higher minor version, the default
+  Minor,
+  /// Roll forward to the lowest higher major version
+  Major,
+  /// Roll forward to the highest patch version
+  LatestPatch,
+  /// Roll forward to the highest minor version
+  LatestMinor,
+  /// Roll forward to the highest major version
+  LatestMajor,
+  /// Only use the exact version requested
+  Disable,
+}
+
+impl fmt::Display for RollForward {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(match self {
+      Self::Minor => "Minor",
+      Self::Major => "Major",
+      Self::LatestPatch => "LatestPatch",
+      Self::LatestMinor => "LatestMinor",
+      Self::LatestMajor => "LatestMajor",
+      Self::Disable => "Disable",
+    })
+  }
+}
+
+#[derive(Debug, Clone)]
+enum RuntimeConfig {
+  /// Runtime config embedded with the bridge assembly
+  Bridge,
+  /// Path to a `.runtimeconfig.json`
+  File(PathBuf),
+  /// Contents of a `.runtimeconfig.json`
+  Json(String),
+  /// Generated from the framework settings of the builder
+  Generated,
+}
+
+/// Configures and installs the [`Global`](crate::runtime::Global) runtime
+///
+/// # Example
+/// ```no_run
+/// use dotnet::runtime::hostfxr::{HostFxrRuntimeBuilder, RollForward};
+///
+/// HostFxrRuntimeBuilder::new()
+///   .framework_version("5.0.0")
+///   .roll_forward(RollForward::LatestMajor)
+///   .property("System.GC.Server", "false")
+///   .install()
+///   .expect("Failed to start runtime");
+/// ```
+#[derive(Debug, Clone)]
+pub struct HostFxrRuntimeBuilder {
+  config: RuntimeConfig,
+  tfm: String,
+  framework_version: String,
+  roll_forward: Option<RollForward>,
+  parameters: Option<HostFxrParameters<'static>>,
+  properties: Vec<(String, String)>,
+  probing_paths: Vec<PathBuf>,
+}
+
+impl HostFxrRuntimeBuilder {
+  pub fn new() -> Self {
+    Self {
+      config: RuntimeConfig::Bridge,
+      tfm: "net5.0".to_string(),
+      framework_version: "5.0.0".to_string(),
+      roll_forward: None,
+      parameters: None,
+      properties: Vec::new(),
+      probing_paths: Vec::new(),
+    }
+  }
+
+  /// Initialize the runtime using the `.runtimeconfig.json` at `path`
+  pub fn runtime_config_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+    self.config = RuntimeConfig::File(path.into());
+    self
+  }
+
+  /// Initialize the runtime using the supplied `.runtimeconfig.json` contents
+  pub fn runtime_config_json<J: Into<String>>(mut self, json: J) -> Self {
+    self.config = RuntimeConfig::Json(json.into());
+    self
+  }
+
+  /// Target framework moniker of the generated runtime config, defaults to `net5.0`
+  pub fn tfm<T: Into<String>>(mut self, tfm: T) -> Self {
+    self.tfm = tfm.into();
+    self.config = RuntimeConfig::Generated;
+    self
+  }
+
+  /// `Microsoft.NETCore.App` version of the generated runtime config, defaults to `5.0.0`
+  pub fn framework_version<V: Into<String>>(mut self, version: V) -> Self {
+    self.framework_version = version.into();
+    self.config = RuntimeConfig::Generated;
+    self
+  }
+
+  /// Roll forward policy of the generated runtime config
+  pub fn roll_forward(mut self, roll_forward: RollForward) -> Self {
+    self.roll_forward = Some(roll_forward);
+    self.config = RuntimeConfig::Generated;
+    self
+  }
+
+  /// Host path and dotnet root used to resolve the runtime
+  pub fn parameters(mut self, parameters: HostFxrParameters<'static>) -> Self {
+    self.parameters = Some(parameters);
+    self
+  }
+
+  /// Set runtime property `name` before the runtime is loaded
+  pub fn property<N, V>(mut self, name: N, value: V) -> Self
+  where
+    N: Into<String>,
+    V: Into<String>,
+  {
+    self.properties.push((name.into(), value.into()));
+    self
+  }
+
+  /// Append `path` to the directories probed for assemblies
+  pub fn probing_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+    self.probing_paths.push(path.into());
+    self
+  }
+
+  /// Initialize the runtime and install it as the [`Global`](crate::runtime::Global)
+  /// runtime
+  ///
+  /// Fails with [`HostFxrError::AlreadyInitialized`] if a runtime was already installed,
+  /// including implicitly by [`Runtime::get`](crate::Runtime::get).
+  pub fn install(self) -> Result<HostFxrRuntime<'static>, HostFxrError> {
+    // Concurrent installs and `Runtime::get` block on the same initialization, so hostfxr
+    // is only ever initialized once
+    let mut built = false;
+    let runtime = CURRENT.get_or_try_init(|| {
+      built = true;
+      self.build()
+    })?;
+
+    if !built {
+      return Err(HostFxrError::AlreadyInitialized);
+    }
+
+    Ok(runtime.clone())
+  }
+
+  pub(super) fn build(self) -> Result<HostFxrRuntime<'static>, HostFxrError> {
+    let config = match &self.config {
+      RuntimeConfig::Bridge => bridge::get_bridge_runtime_config_path()?,
+      RuntimeConfig::File(path) => path.clone(),
+      RuntimeConfig::Json(json) => bridge::write_runtime_config(json)?,
+      RuntimeConfig::Generated => bridge::write_runtime_config(&self.generate_config())?,
+    };
+
+    // Initialize HostFxr
+    let host = HostFxrLibrary::get()?;
+    let host =
+      host.initialize_runtime_config(config.to_string_lossy(), self.parameters)?;
+
+    // Properties are only applied until the runtime is loaded by getting the bridge
+    for (name, value) in self.properties {
+      host.set_runtime_property_value(name, value)?;
+    }
+
+    if !self.probing_paths.is_empty() {
+      let existing = host.get_runtime_property(PROBING_DIRECTORIES).ok();
+      let existing = existing.iter().flat_map(env::split_paths);
+      let paths = env::join_paths(existing.chain(self.probing_paths))
+        .map_err(|_| HostFxrError::InvalidProbingPath)?;
+
+      host.set_runtime_property_value(PROBING_DIRECTORIES, paths.to_string_lossy())?;
+    }
+
+    let host = Arc::new(host);
+    let bridge = match get_bridge(&host)? {
+      Some(bridge) => bridge,
+      None => return Err(HostFxrError::BridgeNone),
+    };
+
+    Ok(HostFxrRuntime { host, bridge })
+  }
+
+  fn generate_config(&self) -> String {
+    let roll_forward = self
+      .roll_forward
+      .map(|roll_forward| format!("\n    \"rollForward\": \"{}\",", roll_forward))
+      .unwrap_or_default();
+
+    format!(
+      r#"{{
+  "runtimeOptions": {{
+    "tfm": "{}",{}
+    "framework": {{
+      "name": "Microsoft.NETCore.App",
+      "version": "{}"
+    }}
+  }}
+}}"#,
+      escape(&self.tfm),
+      roll_forward,
+      escape(&self.framework_version),
+    )
+  }
+}
+
+impl Default for HostFxrRuntimeBuilder {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+/// Escape `value` for use inside a JSON string
+fn escape(value: &str) -> String {
+  value.chars().fold(String::new(), |mut escaped, ch| {
+    match ch {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      ch if ch.is_control() => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+      ch => escaped.push(ch),
+    }
+
+    escaped
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{HostFxrRuntimeBuilder, RollForward};
+
+  #[test]
+  fn test_generate_config() {
+    let config = HostFxrRuntimeBuilder::new()
+      .framework_version("5.0.\"1\"")
+      .roll_forward(RollForward::LatestMajor)
+      .generate_config();
+
+    assert!(config.contains(r#""tfm": "net5.0","#));
+    assert!(config.contains(r#""rollForward": "LatestMajor","#));
+    assert!(config.contains(r#""version": "5.0.\"1\"""#));
+  }
+}
@@ -0,0 +1,376 @@
+use crate::{
+  array::RawArray,
+  marshal::{
+    Blittable, IsBlittable, Marshal, MarshalError, MarshalFrom, MarshalOption, MarshalTo,
+    RawNullable,
+  },
+  types::TypeId,
+};
+use std::{borrow::Cow, convert::TryFrom, ffi::OsStr, fmt};
+
+/// UTF-16 code unit, marshalled as `System.Char`
+///
+/// Unlike [`char`] this can hold any `System.Char` including surrogates.
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Utf16Char(pub u16);
+
+impl Utf16Char {
+  /// Convert to a [`char`], `None` for surrogates
+  pub fn to_char(self) -> Option<char> {
+    char::from_u32(self.0 as _)
+  }
+
+  pub fn is_surrogate(self) -> bool {
+    (0xd800..=0xdfff).contains(&self.0)
+  }
+}
+
+impl From<Utf16Char> for u16 {
+  fn from(ch: Utf16Char) -> Self {
+    ch.0
+  }
+}
+
+impl TryFrom<char> for Utf16Char {
+  type Error = MarshalError;
+
+  /// Fails with [`MarshalError::NonBmpChar`] for characters that take two code units
+  fn try_from(ch: char) -> Result<Self, Self::Error> {
+    u16::try_from(ch as u32)
+      .map(Self)
+      .map_err(|_| MarshalError::NonBmpChar(ch))
+  }
+}
+
+impl Marshal for Utf16Char {
+  type Managed = Self;
+
+  fn id() -> TypeId {
+    TypeId::Char
+  }
+
+  fn blittable() -> Option<IsBlittable<Self>> {
+    Some(IsBlittable::new())
+  }
+}
+
+unsafe impl Blittable for Utf16Char {}
+
+impl MarshalTo for Utf16Char {
+  #[inline]
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    Ok(self)
+  }
+}
+
+impl MarshalFrom for Utf16Char {
+  #[inline]
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    Ok(from)
+  }
+}
+
+/// Marshalled as `System.Char`, only characters of the basic multilingual plane fit
+impl Marshal for char {
+  type Managed = Utf16Char;
+
+  fn id() -> TypeId {
+    TypeId::Char
+  }
+}
+
+impl MarshalOption for char {
+  type Optional = RawNullable<Utf16Char>;
+}
+
+impl MarshalTo for char {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    Utf16Char::try_from(self)
+  }
+}
+
+impl MarshalFrom for char {
+  /// Fails with [`MarshalError::InvalidUnicode`] for surrogates
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    from.to_char().ok_or(MarshalError::InvalidUnicode)
+  }
+}
+
+/// Managed string read in place, the bridge keeps it pinned until this is dropped
+///
+/// .NET strings are UTF-16 and may contain unpaired surrogates, decoding is left to the
+/// caller.
+pub struct ManagedStr(RawArray<u16>);
+
+impl ManagedStr {
+  /// UTF-16 code units of the string
+  pub fn as_utf16(&self) -> &[u16] {
+    self.0.as_slice()
+  }
+
+  /// Length in UTF-16 code units
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Decode the string, fails with [`MarshalError::InvalidUnicode`] on unpaired surrogates
+  pub fn try_to_string(&self) -> Result<String, MarshalError> {
+    String::from_utf16(self.as_utf16()).map_err(|_| MarshalError::InvalidUnicode)
+  }
+
+  /// Decode the string, replacing unpaired surrogates with `U+FFFD`
+  pub fn to_string_lossy(&self) -> String {
+    String::from_utf16_lossy(self.as_utf16())
+  }
+}
+
+impl fmt::Debug for ManagedStr {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Debug::fmt(&self.to_string_lossy(), f)
+  }
+}
+
+impl PartialEq<str> for ManagedStr {
+  fn eq(&self, other: &str) -> bool {
+    self.as_utf16().iter().copied().eq(other.encode_utf16())
+  }
+}
+
+impl PartialEq<&str> for ManagedStr {
+  fn eq(&self, other: &&str) -> bool {
+    self == *other
+  }
+}
+
+impl Marshal for ManagedStr {
+  type Managed = RawArray<u16>;
+
+  fn id() -> TypeId {
+    TypeId::String
+  }
+}
+
+impl MarshalOption for ManagedStr {
+  type Optional = Self::Managed;
+}
+
+impl MarshalFrom for ManagedStr {
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    if from.is_null() {
+      return Err(MarshalError::NullReference);
+    }
+
+    Ok(Self(from))
+  }
+}
+
+/// Opt-in lossy decoding of managed strings, unpaired surrogates are replaced with `U+FFFD`
+/// instead of failing
+///
+/// # Example
+/// ```no_run
+/// use dotnet::{string::Lossy, Runtime};
+/// # fn example<R: Runtime>(rt: R) -> Result<(), R::Error> {
+/// let name = rt.method::<fn() -> Lossy<String>, _>("System.Environment.get_UserName")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Lossy<T>(pub T);
+
+impl<T> Lossy<T> {
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+impl Marshal for Lossy<String> {
+  type Managed = RawArray<u16>;
+
+  fn id() -> TypeId {
+    TypeId::String
+  }
+}
+
+impl MarshalOption for Lossy<String> {
+  type Optional = Self::Managed;
+}
+
+impl MarshalFrom for Lossy<String> {
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    let str = ManagedStr::marshal_from(from)?;
+
+    Ok(Self(str.to_string_lossy()))
+  }
+}
+
+impl Marshal for String {
+  type Managed = RawArray<u16>;
+
+  fn id() -> TypeId {
+    TypeId::String
+  }
+}
+
+impl MarshalOption for String {
+  type Optional = Self::Managed;
+}
+
+impl MarshalTo for String {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    self.as_str().marshal_to()
+  }
+}
+
+impl MarshalOption for &String {
+  type Optional = Self::Managed;
+}
+
+impl MarshalTo for &String {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    self.as_str().marshal_to()
+  }
+}
+
+impl MarshalFrom for String {
+  fn marshal_from(from: Self::Managed) -> Result<Self, MarshalError> {
+    ManagedStr::marshal_from(from)?.try_to_string()
+  }
+}
+
+impl Marshal for &str {
+  type Managed = RawArray<u16>;
+
+  fn id() -> TypeId {
+    TypeId::String
+  }
+}
+
+impl MarshalOption for &str {
+  type Optional = Self::Managed;
+}
+
+impl MarshalTo for &str {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    RawArray::owned(self.encode_utf16().collect())
+  }
+}
+
+impl Marshal for Cow<'_, str> {
+  type Managed = RawArray<u16>;
+
+  fn id() -> TypeId {
+    TypeId::String
+  }
+}
+
+impl MarshalOption for Cow<'_, str> {
+  type Optional = Self::Managed;
+}
+
+impl MarshalTo for Cow<'_, str> {
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    self.as_ref().marshal_to()
+  }
+}
+
+impl Marshal for &OsStr {
+  type Managed = RawArray<u16>;
+
+  fn id() -> TypeId {
+    TypeId::String
+  }
+}
+
+impl MarshalOption for &OsStr {
+  type Optional = Self::Managed;
+}
+
+impl MarshalTo for &OsStr {
+  /// Windows strings are passed as is, including unpaired surrogates, other platforms fail
+  /// with [`MarshalError::InvalidUnicode`] on strings that are not valid UTF-8
+  fn marshal_to(self) -> Result<Self::Managed, MarshalError> {
+    #[cfg(windows)]
+    {
+      use std::os::windows::ffi::OsStrExt;
+
+      RawArray::owned(self.encode_wide().collect())
+    }
+
+    #[cfg(not(windows))]
+    {
+      self
+        .to_str()
+        .ok_or(MarshalError::InvalidUnicode)?
+        .marshal_to()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Lossy, Utf16Char};
+  use crate::{
+    array::RawArray,
+    marshal::{MarshalError, MarshalFrom, MarshalTo},
+  };
+
+  fn managed(units: &[u16]) -> RawArray<u16> {
+    unsafe { RawArray::borrowed(units.as_ptr(), units.len()).unwrap() }
+  }
+
+  #[test]
+  fn test_marshal_to() {
+    let units = "h\u{e9}llo \u{1d11e}".marshal_to().unwrap();
+
+    assert_eq!(
+      units.as_slice(),
+      &[0x68, 0xe9, 0x6c, 0x6c, 0x6f, 0x20, 0xd834, 0xdd1e]
+    );
+  }
+
+  #[test]
+  fn test_marshal_from() {
+    let valid = [0x68, 0xe9, 0xd834, 0xdd1e];
+    let unpaired = [0x68, 0xd834];
+
+    assert_eq!(
+      String::marshal_from(managed(&valid)).unwrap(),
+      "h\u{e9}\u{1d11e}"
+    );
+    assert!(matches!(
+      String::marshal_from(managed(&unpaired)),
+      Err(MarshalError::InvalidUnicode)
+    ));
+    assert_eq!(
+      Lossy::<String>::marshal_from(managed(&unpaired)).unwrap().0,
+      "h\u{fffd}"
+    );
+    assert!(matches!(
+      String::marshal_from(RawArray::null()),
+      Err(MarshalError::NullReference)
+    ));
+  }
+
+  #[test]
+  fn test_char() {
+    assert_eq!(','.marshal_to().unwrap(), Utf16Char(0x2c));
+    assert_eq!('\u{e9}'.marshal_to().unwrap(), Utf16Char(0xe9));
+    assert!(matches!(
+      '\u{1d11e}'.marshal_to(),
+      Err(MarshalError::NonBmpChar('\u{1d11e}'))
+    ));
+
+    assert_eq!(char::marshal_from(Utf16Char(0x2c)).unwrap(), ',');
+    assert!(Utf16Char(0xd834).is_surrogate());
+    assert!(matches!(
+      char::marshal_from(Utf16Char(0xd834)),
+      Err(MarshalError::InvalidUnicode)
+    ));
+  }
+}
@@ -1,4 +1,11 @@
-use crate::{class::Class, runtime::Global, Runtime};
+use crate::{
+  class::Class,
+  error::{RuntimeError, RuntimeResult},
+  marshal::{MarshalFrom, MarshalTo},
+  method::{Arguments, Binding, Constructor, MemberKind, Method},
+  runtime::Global,
+  Runtime,
+};
 use std::ops::Deref;
 
 #[repr(C, u8)]
@@ -25,6 +32,8 @@ pub enum TypeId {
   Array(Box<TypeId>),
   Nullable(Box<TypeId>),
   Enumerable(Box<TypeId>),
+
+  Void,
 }
 
 #[derive(Debug)]
@@ -35,13 +44,67 @@ impl<R: Runtime> Type<R> {
     Self(class)
   }
 
-  pub fn get_name(&self) -> Result<String, R::Error> {
+  pub fn get_name(&self) -> RuntimeResult<String, R> {
     self.get_property("Name")
   }
 
-  pub fn is_class(&self) -> Result<bool, R::Error> {
+  /// Name including the namespace, e.g. `System.Version`
+  pub fn get_full_name(&self) -> RuntimeResult<String, R> {
+    self.get_property("FullName")
+  }
+
+  pub fn is_class(&self) -> RuntimeResult<bool, R> {
     self.get_property("IsClass")
   }
+
+  /// Call static method `name` of this type, picking the overload using the argument and
+  /// return types of `M`
+  pub fn call_static<M, A>(&self, name: &str, args: A) -> RuntimeResult<M::Ret, R>
+  where
+    M: Method<A>,
+    M::Fn: Method<A>,
+  {
+    self.call_member::<M, A>(name, args, Binding::Static)
+  }
+
+  /// Create a new instance of this type, picking the constructor using the types of `args`
+  pub fn construct<A: Arguments>(&self, args: A) -> RuntimeResult<Class<R>, R> {
+    let rt = R::get().map_err(RuntimeError::Runtime)?;
+    let ctor = rt
+      .member::<Constructor<R>, A>(
+        self.handle(),
+        ".ctor",
+        MemberKind::Constructor,
+        Binding::Static,
+      )
+      .map_err(RuntimeError::Runtime)?;
+
+    ctor.call(args)
+  }
+
+  pub fn get_static_field<M: MarshalFrom>(&self, name: &str) -> RuntimeResult<M, R> {
+    self.get_member(name, MemberKind::Field, Binding::Static)
+  }
+
+  pub fn set_static_field<M: MarshalTo>(
+    &self,
+    name: &str,
+    value: M,
+  ) -> RuntimeResult<(), R> {
+    self.set_member(name, value, MemberKind::Field, Binding::Static)
+  }
+
+  pub fn get_static_property<M: MarshalFrom>(&self, name: &str) -> RuntimeResult<M, R> {
+    self.get_member(name, MemberKind::Property, Binding::Static)
+  }
+
+  pub fn set_static_property<M: MarshalTo>(
+    &self,
+    name: &str,
+    value: M,
+  ) -> RuntimeResult<(), R> {
+    self.set_member(name, value, MemberKind::Property, Binding::Static)
+  }
 }
 
 impl<R: Runtime> Deref for Type<R> {